@@ -3,12 +3,80 @@
 //! Provides types and operations for standard poker card.
 
 use core::fmt;
+use std::str::FromStr;
 
 use crate::{rank::Rank, suit::Suit};
 
+/// Errors returned when parsing a `Card`, a `Rank` or a `Suit` from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    /// The text is empty.
+    Empty,
+    /// The text doesn't name a rank, empty if the rank is missing.
+    InvalidRank(String),
+    /// The text doesn't name a suit, empty if the suit is missing.
+    InvalidSuit(String),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseCardError::Empty => write!(f, "no card given"),
+            ParseCardError::InvalidRank(text) if text.is_empty() => write!(
+                f,
+                "missing rank, expected A, 2 to 10, J, Q, K or a name like Queen"
+            ),
+            ParseCardError::InvalidSuit(text) if text.is_empty() => write!(
+                f,
+                "missing suit, expected S, D, C, H, a suit emoji or a name like Clubs"
+            ),
+            ParseCardError::InvalidRank(text) => write!(
+                f,
+                "invalid rank `{text}`, expected A, 2 to 10, J, Q, K or a name like Queen"
+            ),
+            ParseCardError::InvalidSuit(text) => write!(
+                f,
+                "invalid suit `{text}`, expected S, D, C, H, a suit emoji or a name like Clubs"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Errors returned when converting a number into a `Card`, a `Rank` or a
+/// `Suit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardIndexError {
+    /// The number is not a card index, from 0 to 51.
+    InvalidIndex(u8),
+    /// The number is not a rank, from 1 (Ace) to 13 (King).
+    InvalidRank(u8),
+    /// The number is not a suit, from 0 to 3.
+    InvalidSuit(u8),
+}
+
+impl fmt::Display for CardIndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardIndexError::InvalidIndex(index) => {
+                write!(f, "invalid card index {index}, expected 0 to 51")
+            }
+            CardIndexError::InvalidRank(rank) => {
+                write!(f, "invalid rank number {rank}, expected 1 to 13")
+            }
+            CardIndexError::InvalidSuit(suit) => {
+                write!(f, "invalid suit number {suit}, expected 0 to 3")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardIndexError {}
+
 /// # Examples
 /// ```
-/// use your_crate::{Card, Suit, Rank};
+/// use dungeon_and_cards::{Card, Suit, Rank};
 ///
 /// let card = Card::new(Suit::Spades, Rank::Ace);
 /// assert_eq!(card.to_string(), "♠️  A");
@@ -33,6 +101,40 @@ impl Card {
     pub fn rank(&self) -> Rank {
         self.rank
     }
+
+    /// Returns the index of the card, from 0 to 51, in the order of a new
+    /// deck: the suits as numbered by `u8::from(suit)`, 13 cards each, from
+    /// Ace to King. That is `13 * suit + rank - 1`, e.g. 0 for the Ace of
+    /// Spades and 51 for the King of Hearts.
+    ///
+    /// The order is stable: indices can be stored and exchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use dungeon_and_cards::{Card, Rank, Suit};
+    ///
+    /// let queen = Card::new(Suit::Clubs, Rank::Queen);
+    /// assert_eq!(queen.to_index(), 37);
+    /// assert_eq!(Card::from_index(37), Ok(queen));
+    /// assert!(Card::from_index(52).is_err());
+    /// ```
+    pub fn to_index(&self) -> u8 {
+        u8::from(self.suit) * 13 + u8::from(self.rank) - 1
+    }
+
+    /// Returns the card of an index given by `Card::to_index`.
+    ///
+    /// # Errors
+    /// `CardIndexError::InvalidIndex` if the index is 52 or more.
+    pub fn from_index(index: u8) -> Result<Self, CardIndexError> {
+        if index >= 52 {
+            return Err(CardIndexError::InvalidIndex(index));
+        }
+        Ok(Self {
+            suit: Suit::try_from(index / 13)?,
+            rank: Rank::try_from(index % 13 + 1)?,
+        })
+    }
 }
 
 impl fmt::Display for Card {
@@ -41,6 +143,81 @@ impl fmt::Display for Card {
     }
 }
 
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    /// Parses a card, ignoring case, from:
+    /// - its `Display` output, the suit emoji then the rank: `♣️  Q`
+    /// - the short notation, the rank then the suit letter: `QC`, `10h`, `As`
+    /// - its long name: `Queen of Clubs`
+    ///
+    /// # Examples
+    /// ```
+    /// use dungeon_and_cards::{Card, Rank, Suit};
+    ///
+    /// let queen = Card::new(Suit::Clubs, Rank::Queen);
+    /// assert_eq!("QC".parse(), Ok(queen));
+    /// assert_eq!("queen of clubs".parse(), Ok(queen));
+    /// assert_eq!(queen.to_string().parse(), Ok(queen));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        if text.is_empty() {
+            return Err(ParseCardError::Empty);
+        }
+
+        // Long name: `Queen of Clubs`.
+        let lowercase = text.to_lowercase();
+        if let Some((rank, suit)) = lowercase.split_once(" of ") {
+            return Ok(Card::new(suit.parse()?, rank.parse()?));
+        }
+
+        // Suit and rank apart: `♣️  Q`, or the other way around: `Q ♣️`.
+        if let Some((first, second)) = text.split_once(char::is_whitespace) {
+            return match first.parse::<Suit>() {
+                Ok(suit) => Ok(Card::new(suit, second.parse()?)),
+                Err(_) => Ok(Card::new(second.parse()?, first.parse()?)),
+            };
+        }
+
+        // Short notation: `QC`, `10h`, or with an emoji: `Q♣️`, `♣️Q`.
+        let starts_with_rank = text.starts_with(|c: char| c.is_ascii_alphanumeric());
+        if starts_with_rank {
+            // A number, or a single letter.
+            let rank_len = match text.find(|c: char| !c.is_ascii_digit()) {
+                Some(0) => text.chars().next().map_or(0, char::len_utf8),
+                Some(len) => len,
+                None => text.len(),
+            };
+            let (rank, suit) = text.split_at(rank_len);
+            Ok(Card::new(suit.parse()?, rank.parse()?))
+        } else {
+            let suit_len = text
+                .find(|c: char| c.is_ascii_alphanumeric())
+                .unwrap_or(text.len());
+            let (suit, rank) = text.split_at(suit_len);
+            Ok(Card::new(suit.parse()?, rank.parse()?))
+        }
+    }
+}
+
+/// A card is serialized in the short notation, the rank then the suit
+/// letter, e.g. `"QC"`, and deserialized from any text `FromStr` accepts.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{}{}", self.rank, self.suit.letter()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,4 +228,123 @@ mod tests {
 
         assert_eq!(card.to_string(), "♣️  Q")
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_cards_in_short_notation() {
+        let queen = Card::new(Suit::Clubs, Rank::Queen);
+
+        assert_eq!(serde_json::to_string(&queen).unwrap(), r#""QC""#);
+        assert_eq!(serde_json::to_string(&Suit::Clubs).unwrap(), r#""C""#);
+        assert_eq!(serde_json::to_string(&Rank::Ten).unwrap(), r#""10""#);
+        assert_eq!(serde_json::from_str::<Card>(r#""QC""#).unwrap(), queen);
+        assert_eq!(
+            serde_json::from_str::<Card>(r#""Queen of Clubs""#).unwrap(),
+            queen
+        );
+        assert_eq!(
+            serde_json::from_str::<Suit>(r#""♣️""#).unwrap(),
+            Suit::Clubs
+        );
+        assert_eq!(serde_json::from_str::<Rank>(r#""q""#).unwrap(), Rank::Queen);
+        assert!(serde_json::from_str::<Card>(r#""QX""#).is_err());
+        assert!(serde_json::from_str::<Card>("12").is_err());
+    }
+
+    #[test]
+    fn indices_follow_the_order_of_a_new_deck() {
+        use strum::IntoEnumIterator;
+
+        let cards =
+            Suit::iter().flat_map(|suit| Rank::iter().map(move |rank| Card::new(suit, rank)));
+        for (index, card) in (0..).zip(cards) {
+            assert_eq!(card.to_index(), index);
+            assert_eq!(Card::from_index(index), Ok(card));
+        }
+        assert_eq!(Card::from_index(52), Err(CardIndexError::InvalidIndex(52)));
+        assert_eq!(
+            Card::from_index(u8::MAX),
+            Err(CardIndexError::InvalidIndex(u8::MAX))
+        );
+    }
+
+    #[test]
+    fn parse_every_card_from_its_display() {
+        use strum::IntoEnumIterator;
+
+        for suit in Suit::iter() {
+            for rank in Rank::iter() {
+                let card = Card::new(suit, rank);
+                assert_eq!(card.to_string().parse(), Ok(card));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_short_notation() {
+        assert_eq!("QC".parse(), Ok(Card::new(Suit::Clubs, Rank::Queen)));
+        assert_eq!("10h".parse(), Ok(Card::new(Suit::Hearts, Rank::Ten)));
+        assert_eq!("As".parse(), Ok(Card::new(Suit::Spades, Rank::Ace)));
+        assert_eq!(" 2d ".parse(), Ok(Card::new(Suit::Diamonds, Rank::Two)));
+        assert_eq!("Q♣️".parse(), Ok(Card::new(Suit::Clubs, Rank::Queen)));
+        assert_eq!("♣Q".parse(), Ok(Card::new(Suit::Clubs, Rank::Queen)));
+        assert_eq!("Q ♣️".parse(), Ok(Card::new(Suit::Clubs, Rank::Queen)));
+    }
+
+    #[test]
+    fn parse_long_names() {
+        assert_eq!(
+            "Queen of Clubs".parse(),
+            Ok(Card::new(Suit::Clubs, Rank::Queen))
+        );
+        assert_eq!(
+            "ten of hearts".parse(),
+            Ok(Card::new(Suit::Hearts, Rank::Ten))
+        );
+        assert_eq!(
+            "ACE OF SPADES".parse(),
+            Ok(Card::new(Suit::Spades, Rank::Ace))
+        );
+        assert_eq!(
+            "7 of Diamonds".parse(),
+            Ok(Card::new(Suit::Diamonds, Rank::Seven))
+        );
+    }
+
+    #[test]
+    fn parse_errors_name_the_invalid_part() {
+        assert_eq!("".parse::<Card>(), Err(ParseCardError::Empty));
+        assert_eq!(
+            "1H".parse::<Card>(),
+            Err(ParseCardError::InvalidRank("1".to_string()))
+        );
+        assert_eq!(
+            "QX".parse::<Card>(),
+            Err(ParseCardError::InvalidSuit("X".to_string()))
+        );
+        assert_eq!(
+            "Q".parse::<Card>(),
+            Err(ParseCardError::InvalidSuit(String::new()))
+        );
+        assert_eq!(
+            "Queen of Swords".parse::<Card>(),
+            Err(ParseCardError::InvalidSuit("swords".to_string()))
+        );
+        assert_eq!(
+            "Prince of Clubs".parse::<Card>(),
+            Err(ParseCardError::InvalidRank("prince".to_string()))
+        );
+        assert_eq!(
+            "♣️  Z".parse::<Card>(),
+            Err(ParseCardError::InvalidRank("Z".to_string()))
+        );
+        assert_eq!(
+            ParseCardError::InvalidSuit("X".to_string()).to_string(),
+            "invalid suit `X`, expected S, D, C, H, a suit emoji or a name like Clubs"
+        );
+        assert_eq!(
+            ParseCardError::InvalidSuit(String::new()).to_string(),
+            "missing suit, expected S, D, C, H, a suit emoji or a name like Clubs"
+        );
+    }
 }
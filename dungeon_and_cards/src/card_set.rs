@@ -0,0 +1,290 @@
+//! A module for sets of playing cards.
+//!
+//! Provides `CardSet`, a set of cards packed in the bits of a single `u64`:
+//! copying, hashing and comparing one is as cheap as for an integer, which
+//! suits the searches and the lookups done at every move.
+
+use core::fmt;
+
+use crate::card::Card;
+
+/// Bits of the 52 cards.
+const ALL: u64 = (1 << 52) - 1;
+
+/// Returns the bit of a card, at its index.
+fn bit(card: Card) -> u64 {
+    1 << card.to_index()
+}
+
+/// Returns the card at the index of a bit.
+fn card_at(index: u32) -> Card {
+    Card::from_index(index as u8).expect("Only the bits of the 52 cards are set")
+}
+
+/// A set of cards.
+///
+/// Each card has its own bit, at its `Card::to_index`, so every operation
+/// takes constant time.
+/// Iteration goes in the order of a new deck: by suit, then from Ace to King.
+///
+/// # Examples
+/// ```
+/// use dungeon_and_cards::{Card, CardSet, Rank, Suit};
+///
+/// let ace = Card::new(Suit::Hearts, Rank::Ace);
+/// let king = Card::new(Suit::Spades, Rank::King);
+/// let hand: CardSet = [ace, king].into_iter().collect();
+///
+/// assert!(hand.contains(ace));
+/// assert_eq!(hand.len(), 2);
+/// assert_eq!(hand.iter().collect::<Vec<_>>(), vec![king, ace]);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CardSet {
+    /// One bit per card, by index.
+    bits: u64,
+}
+
+impl CardSet {
+    /// Creates an empty set.
+    pub const fn new() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Creates the set of the 52 cards.
+    pub const fn full() -> Self {
+        Self { bits: ALL }
+    }
+
+    /// Adds a card to the set.
+    ///
+    /// # Returns
+    /// `true` if the card wasn't in the set already
+    pub fn insert(&mut self, card: Card) -> bool {
+        let bit = bit(card);
+        let inserted = self.bits & bit == 0;
+        self.bits |= bit;
+        inserted
+    }
+
+    /// Removes a card from the set.
+    ///
+    /// # Returns
+    /// `true` if the card was in the set
+    pub fn remove(&mut self, card: Card) -> bool {
+        let bit = bit(card);
+        let removed = self.bits & bit != 0;
+        self.bits &= !bit;
+        removed
+    }
+
+    /// Returns `true` if the card is in the set.
+    pub fn contains(&self, card: Card) -> bool {
+        self.bits & bit(card) != 0
+    }
+
+    /// Returns the number of cards in the set.
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    /// Returns `true` if the set holds no card.
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns the cards in either set.
+    pub fn union(self, other: CardSet) -> CardSet {
+        Self {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    /// Returns the cards in both sets.
+    pub fn intersection(self, other: CardSet) -> CardSet {
+        Self {
+            bits: self.bits & other.bits,
+        }
+    }
+
+    /// Returns the cards of this set that are not in `other`.
+    pub fn difference(self, other: CardSet) -> CardSet {
+        Self {
+            bits: self.bits & !other.bits,
+        }
+    }
+
+    /// Returns the cards missing from the set, out of the 52 cards.
+    pub fn complement(self) -> CardSet {
+        Self::full().difference(self)
+    }
+
+    /// Returns an iterator over the cards, in the order of a new deck.
+    pub fn iter(&self) -> CardSetIter {
+        CardSetIter { bits: self.bits }
+    }
+}
+
+impl fmt::Debug for CardSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        let mut set = Self::new();
+        set.extend(cards);
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = &'a Card>>(cards: I) -> Self {
+        cards.into_iter().copied().collect()
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, cards: I) {
+        for card in cards {
+            self.insert(card);
+        }
+    }
+}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the cards of a `CardSet`, in the order of a new deck.
+#[derive(Debug, Clone)]
+pub struct CardSetIter {
+    /// Bits of the cards not yielded yet.
+    bits: u64,
+}
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.bits == 0 {
+            return None;
+        }
+        let index = self.bits.trailing_zeros();
+        // Clears the lowest bit.
+        self.bits &= self.bits - 1;
+        Some(card_at(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for CardSetIter {
+    fn next_back(&mut self) -> Option<Card> {
+        if self.bits == 0 {
+            return None;
+        }
+        let index = 63 - self.bits.leading_zeros();
+        self.bits &= !(1 << index);
+        Some(card_at(index))
+    }
+}
+
+impl ExactSizeIterator for CardSetIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    use crate::{rank::Rank, suit::Suit};
+
+    /// The 52 cards, in the order of a new deck.
+    fn all_cards() -> impl Iterator<Item = Card> {
+        Suit::iter().flat_map(|suit| Rank::iter().map(move |rank| Card::new(suit, rank)))
+    }
+
+    #[test]
+    fn full_set_holds_every_card_in_order() {
+        let full = CardSet::full();
+
+        assert_eq!(full.len(), 52);
+        assert!(all_cards().all(|card| full.contains(card)));
+        assert!(full.iter().eq(all_cards()));
+        assert!(full
+            .iter()
+            .rev()
+            .eq(all_cards().collect::<Vec<_>>().into_iter().rev()));
+        assert!(full.complement().is_empty());
+    }
+
+    #[test]
+    fn insert_and_remove_report_changes() {
+        let queen = Card::new(Suit::Clubs, Rank::Queen);
+        let mut set = CardSet::new();
+
+        assert!(set.is_empty());
+        assert!(set.insert(queen));
+        assert!(!set.insert(queen));
+        assert!(set.contains(queen));
+        assert_eq!(set.len(), 1);
+
+        assert!(set.remove(queen));
+        assert!(!set.remove(queen));
+        assert!(!set.contains(queen));
+        assert_eq!(set, CardSet::new());
+    }
+
+    #[test]
+    fn set_operations() {
+        let ace = Card::new(Suit::Spades, Rank::Ace);
+        let two = Card::new(Suit::Diamonds, Rank::Two);
+        let king = Card::new(Suit::Hearts, Rank::King);
+        let left: CardSet = [ace, two].into_iter().collect();
+        let right: CardSet = [two, king].into_iter().collect();
+
+        assert_eq!(
+            left.union(right).iter().collect::<Vec<_>>(),
+            [ace, two, king]
+        );
+        assert_eq!(left.intersection(right).iter().collect::<Vec<_>>(), [two]);
+        assert_eq!(left.difference(right).iter().collect::<Vec<_>>(), [ace]);
+        assert_eq!(left.complement().len(), 50);
+        assert!(!left.complement().contains(ace));
+    }
+
+    #[test]
+    fn iterator_knows_its_length() {
+        let set: CardSet = all_cards().step_by(3).collect();
+        let mut cards = set.iter();
+
+        assert_eq!(cards.len(), 18);
+        cards.next();
+        cards.next_back();
+        assert_eq!(cards.len(), 16);
+    }
+
+    #[test]
+    fn debug_lists_the_cards() {
+        let set: CardSet = [Card::new(Suit::Clubs, Rank::Two)].into_iter().collect();
+        assert_eq!(format!("{set:?}"), "{Card { suit: Clubs, rank: Two }}");
+    }
+}
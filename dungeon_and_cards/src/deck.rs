@@ -3,22 +3,55 @@
 //! Provides types and operations for standard 52-card decks with support for
 //! banned cards and various deck operations.
 
-use rand::seq::SliceRandom;
-use std::collections::HashSet;
-use strum::IntoEnumIterator;
+use core::fmt;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use crate::card::Card;
-use crate::rank::Rank;
-use crate::suit::Suit;
+use crate::card_set::CardSet;
+
+/// Errors returned by the fallible `Deck` operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeckError {
+    /// More cards were requested than the deck holds.
+    NotEnoughCards {
+        /// Number of cards requested.
+        requested: usize,
+        /// Number of cards left in the deck.
+        available: usize,
+    },
+    /// The card is not in the deck (already drawn).
+    CardNotFound(Card),
+    /// The card has been banned from the deck, so it can't be in it.
+    BannedCard(Card),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckError::NotEnoughCards {
+                requested,
+                available,
+            } => write!(
+                f,
+                "can't draw {requested} cards, only {available} left in the deck"
+            ),
+            DeckError::CardNotFound(card) => write!(f, "{card} is not in the deck"),
+            DeckError::BannedCard(card) => write!(f, "{card} is banned from the deck"),
+        }
+    }
+}
+
+impl std::error::Error for DeckError {}
 
 /// A deck of playing cards with optional banned cards.
 ///
 /// # Examples
 /// ```
-/// use your_crate::{Deck, Card, Suit, Rank};
+/// use dungeon_and_cards::{Deck, Card, Suit, Rank};
 ///
 /// // Create a deck with banned cards
-/// let banned = Card { suit: Suit::Hearts, rank: Rank::Ace };
+/// let banned = Card::new(Suit::Hearts, Rank::Ace);
 /// let deck = Deck::builder().ban_card(banned).build();
 ///
 /// assert_eq!(deck.len(), 51);
@@ -29,8 +62,12 @@ pub struct Deck {
     cards: Vec<Card>,
     /// The original size of the deck (excluding banned cards)
     size: usize,
+    /// The same cards as `cards`, for fast lookups
+    card_set: CardSet,
     /// Cards that are banned from being in the deck
-    banned_cards: Option<HashSet<Card>>,
+    banned_cards: CardSet,
+    /// Seed of the shuffle applied at construction, if any
+    seed: Option<u64>,
 }
 
 impl Deck {
@@ -45,46 +82,40 @@ impl Deck {
     /// Constructs a new deck with optional banned cards.
     ///
     /// # Arguments
-    /// * `banned_cards` - Optional set of cards to exclude from the deck
-    fn new(banned_cards: Option<HashSet<Card>>) -> Self {
-        // Collect iters for `suits` and `ranks`.
-        let suits = Suit::iter();
-        let ranks = Rank::iter();
-
-        // Pre-allocate vector size.
-        #[allow(unused_assignments)]
-        let mut cards = Vec::with_capacity(52 - banned_cards.as_ref().map_or(0, |b| b.len()));
-
-        // NOTE:
-        // `map` would give you 4 piles (one per suit), each with 13 cards
-        // `flat_map` automatically spreads all 52 cards on one table.
-        cards = if let Some(ref banned_cards) = banned_cards {
-            suits
-                .into_iter()
-                .flat_map(|suit| {
-                    ranks
-                        .clone() // One rank iterator per suit, so clone needed. (No clone would consume the iterator at the first suit)
-                        .into_iter()
-                        .map(move |rank| Card::new(suit, rank))
-                })
-                .filter(|card| !banned_cards.contains(card))
-                .collect::<Vec<_>>()
-        } else {
-            suits
-                .into_iter()
-                .flat_map(|suit| {
-                    ranks
-                        .clone() // One rank iterator per suit, so clone needed. (No clone would consume the iterator at the first suit)
-                        .into_iter()
-                        .map(move |rank| Card::new(suit, rank))
-                })
-                .collect::<Vec<_>>()
+    /// * `banned_cards` - Set of cards to exclude from the deck
+    /// * `seed` - Optional seed to shuffle the deck with
+    fn new(banned_cards: CardSet, seed: Option<u64>) -> Self {
+        // One card per suit and rank, in a single pile.
+        let card_set = banned_cards.complement();
+        let cards: Vec<Card> = card_set.iter().collect();
+
+        let mut deck = Deck {
+            size: cards.len(),
+            cards,
+            card_set,
+            banned_cards,
+            seed,
         };
+        if let Some(seed) = seed {
+            deck.shuffle_with(&mut ChaCha8Rng::seed_from_u64(seed));
+        }
+        deck
+    }
 
+    /// Rebuilds a deck in a given state, e.g. from a save file.
+    ///
+    /// # Arguments
+    /// * `cards` - The cards left in the deck, from the top
+    /// * `banned_cards` - Set of cards excluded from the deck
+    /// * `seed` - The seed the deck was shuffled with at construction, if any
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_parts(cards: Vec<Card>, banned_cards: CardSet, seed: Option<u64>) -> Self {
         Deck {
-            size: cards.len(),
+            size: 52 - banned_cards.len(),
+            card_set: cards.iter().collect(),
             cards,
-            banned_cards: banned_cards,
+            banned_cards,
+            seed,
         }
     }
 
@@ -96,7 +127,17 @@ impl Deck {
     /// # Returns
     /// `true` if the card is in the deck, `false` otherwise
     pub fn contains(&self, card: &Card) -> bool {
-        self.cards.contains(card)
+        self.card_set.contains(*card)
+    }
+
+    /// Returns the cards in the deck, in no order.
+    pub(crate) fn card_set(&self) -> CardSet {
+        self.card_set
+    }
+
+    /// Returns an iterator over the cards in the deck, from the top.
+    pub fn iter(&self) -> impl Iterator<Item = &Card> {
+        self.cards.iter()
     }
 
     /// Draws cards from the top of the deck.
@@ -107,35 +148,75 @@ impl Deck {
     /// # Returns
     /// A vector containing the drawn cards
     ///
-    /// # Panics
-    /// Panics if attempting to draw more cards than are in the deck
+    /// # Errors
+    /// `DeckError::NotEnoughCards` if attempting to draw more cards than are
+    /// in the deck. The deck is left untouched.
     ///
     /// # Examples
     /// ```
-    /// use your_crate::Deck;
+    /// use dungeon_and_cards::Deck;
     ///
     /// let mut deck = Deck::builder().build();
-    /// let cards = deck.draw(5);
+    /// let cards = deck.draw(5).unwrap();
     /// assert_eq!(cards.len(), 5);
     /// assert_eq!(deck.len(), 47);
     /// ```
-    pub fn draw(&mut self, number_of_draws: usize) -> Vec<Card> {
+    pub fn draw(&mut self, number_of_draws: usize) -> Result<Vec<Card>, DeckError> {
+        let len = self.len();
+        if number_of_draws > len {
+            return Err(DeckError::NotEnoughCards {
+                requested: number_of_draws,
+                available: len,
+            });
+        }
+
+        Ok(self.draw_up_to(number_of_draws))
+    }
+
+    /// Draws at most `number_of_draws` cards from the top of the deck.
+    ///
+    /// Unlike `draw`, it never fails: when the deck runs short,
+    /// all the remaining cards are returned.
+    pub fn draw_up_to(&mut self, number_of_draws: usize) -> Vec<Card> {
         if number_of_draws == 0 {
             return Vec::new(); // Edge case: avoid unnecessary allocation
         }
 
-        let len = self.len();
-        if number_of_draws > len {
-            panic!("You are drawing too many cards from the deck!"); // TODO: generate error
+        // Collecting cards from the top.
+        let number_of_draws = number_of_draws.min(self.len());
+        let cards: Vec<Card> = self.cards.drain(..number_of_draws).collect();
+        for card in &cards {
+            self.card_set.remove(*card);
+        }
+        cards
+    }
+
+    /// Pulls a specific card out of the deck, wherever it is.
+    ///
+    /// # Errors
+    /// - `DeckError::BannedCard` if the card is banned from this deck
+    /// - `DeckError::CardNotFound` if the card has already been drawn
+    pub fn pull(&mut self, card: &Card) -> Result<Card, DeckError> {
+        if self.is_banned(card) {
+            return Err(DeckError::BannedCard(*card));
+        }
+        if !self.card_set.remove(*card) {
+            return Err(DeckError::CardNotFound(*card));
         }
 
-        // Collecting cards from the top.
-        self.cards.drain(..number_of_draws).collect()
+        let position = self
+            .cards
+            .iter()
+            .position(|c| c == card)
+            .expect("The card set holds the same cards");
+
+        Ok(self.cards.remove(position))
     }
 
     /// This method puts at the bottom of the deck
     /// an array of card in the given order.
     pub fn bottom(&mut self, cards: &mut Vec<Card>) {
+        self.card_set.extend(cards.iter().copied());
         self.cards.append(cards);
     }
 
@@ -149,39 +230,96 @@ impl Deck {
         self.cards.len()
     }
 
+    /// Returns `true` if the card has been banned from the deck.
+    pub fn is_banned(&self, card: &Card) -> bool {
+        self.banned_cards.contains(*card)
+    }
+
+    /// Returns the seed the deck was shuffled with at construction, if any.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
     /// Resets the deck to its original state (excluding banned cards).
+    ///
+    /// A seeded deck is shuffled again with its seed, getting back the same order.
     pub fn reset(&mut self) {
-        *self = Self::new(self.banned_cards.clone());
+        *self = Self::new(self.banned_cards, self.seed);
     }
 
     /// Shuffles the deck randomly.
     pub fn shuffle(&mut self) {
-        self.cards.shuffle(&mut rand::rng());
+        self.shuffle_with(&mut rand::rng());
+    }
+
+    /// Shuffles the deck with the given random generator.
+    ///
+    /// With a seeded generator the resulting order is reproducible.
+    ///
+    /// # Examples
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    /// use dungeon_and_cards::Deck;
+    ///
+    /// let mut deck = Deck::builder().build();
+    /// let mut other = deck.clone();
+    /// deck.shuffle_with(&mut ChaCha8Rng::seed_from_u64(42));
+    /// other.shuffle_with(&mut ChaCha8Rng::seed_from_u64(42));
+    /// assert_eq!(deck, other);
+    /// ```
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+    }
+
+    /// Shuffles the `count` cards at the top of the deck with the given
+    /// random generator. The cards below keep their order.
+    pub(crate) fn shuffle_top_with<R: Rng + ?Sized>(&mut self, count: usize, rng: &mut R) {
+        let count = count.min(self.len());
+        self.cards[..count].shuffle(rng);
     }
 }
 
 /// Builder for configuring and constructing a `Deck`.
 ///
-/// Provides a fluent interface for specifying banned cards before
-/// constructing the deck.
+/// Provides a fluent interface for specifying banned cards and the
+/// shuffle seed before constructing the deck.
 ///
 /// # Examples
 /// ```
-/// use your_crate::{DeckBuilder, Card, Suit, Rank};
+/// use dungeon_and_cards::{DeckBuilder, Card, Suit, Rank};
 ///
 /// let deck = DeckBuilder::new()
-///     .ban_card(Card { suit: Suit::Spades, rank: Rank::Ace })
-///     .ban_card(Card { suit: Suit::Hearts, rank: Rank::King })
+///     .ban_card(Card::new(Suit::Spades, Rank::Ace))
+///     .ban_card(Card::new(Suit::Hearts, Rank::King))
+///     .seed(42)
 ///     .build();
 /// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "DeckConfig", into = "DeckConfig")
+)]
 pub struct DeckBuilder {
-    banned_cards: Option<HashSet<Card>>,
+    banned_cards: CardSet,
+    seed: Option<u64>,
+}
+
+impl Default for DeckBuilder {
+    /// Same as `DeckBuilder::new`.
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DeckBuilder {
     /// Creates a new deck builder with no banned cards.
     pub fn new() -> Self {
-        Self { banned_cards: None }
+        Self {
+            banned_cards: CardSet::new(),
+            seed: None,
+        }
     }
 
     /// Bans a single card from appearing in the deck.
@@ -189,9 +327,7 @@ impl DeckBuilder {
     /// # Arguments
     /// * `card` - The card to ban
     pub fn ban_card(mut self, card: Card) -> Self {
-        self.banned_cards
-            .get_or_insert_with(HashSet::new)
-            .insert(card);
+        self.banned_cards.insert(card);
         self
     }
 
@@ -200,15 +336,105 @@ impl DeckBuilder {
     /// # Arguments
     /// * `cards` - An iterator of cards to ban
     pub fn ban_cards(mut self, cards: impl IntoIterator<Item = Card>) -> Self {
-        self.banned_cards
-            .get_or_insert_with(HashSet::new)
-            .extend(cards);
+        self.banned_cards.extend(cards);
         self
     }
 
-    /// Constructs the deck with the configured banned cards.
+    /// Shuffles the deck at construction with a seeded random generator.
+    ///
+    /// The same seed (and banned cards) always gives the same card order.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed of the shuffle
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Constructs the deck with the configured banned cards,
+    /// shuffled if a seed has been given.
     pub fn build(self) -> Deck {
-        Deck::new(self.banned_cards)
+        Deck::new(self.banned_cards, self.seed)
+    }
+}
+
+/// A deck is serialized as its cards from the top, its banned cards and
+/// its seed, e.g. `{"cards":["QC","10H"],"banned_cards":["AH"],"seed":42}`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Deck {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DeckState {
+            cards: self.cards.clone(),
+            banned_cards: self.banned_cards.iter().collect(),
+            seed: self.seed,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Deck {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = DeckState::deserialize(deserializer)?;
+        let banned_cards: CardSet = state.banned_cards.into_iter().collect();
+        let mut listed = CardSet::new();
+        for card in &state.cards {
+            if banned_cards.contains(*card) {
+                return Err(serde::de::Error::custom(DeckError::BannedCard(*card)));
+            }
+            if !listed.insert(*card) {
+                return Err(serde::de::Error::custom(format!(
+                    "{card} is twice in the deck"
+                )));
+            }
+        }
+
+        Ok(Deck::from_parts(state.cards, banned_cards, state.seed))
+    }
+}
+
+/// Serialized form of a `Deck`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DeckState {
+    /// Cards left in the deck, from the top.
+    cards: Vec<Card>,
+    /// Banned cards, in the order of a new deck.
+    #[serde(default)]
+    banned_cards: Vec<Card>,
+    /// Seed of the shuffle applied at construction, if any.
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+/// Serialized form of a `DeckBuilder`, e.g. `{"banned_cards":["AH"],"seed":42}`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DeckConfig {
+    /// Banned cards, in the order of a new deck.
+    #[serde(default)]
+    banned_cards: Vec<Card>,
+    /// Seed of the shuffle, if any.
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+#[cfg(feature = "serde")]
+impl From<DeckBuilder> for DeckConfig {
+    fn from(builder: DeckBuilder) -> Self {
+        Self {
+            banned_cards: builder.banned_cards.iter().collect(),
+            seed: builder.seed,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<DeckConfig> for DeckBuilder {
+    fn from(config: DeckConfig) -> Self {
+        let mut builder = DeckBuilder::new().ban_cards(config.banned_cards);
+        builder.seed = config.seed;
+        builder
     }
 }
 
@@ -216,6 +442,8 @@ impl DeckBuilder {
 mod tests {
     use super::*;
 
+    use crate::{rank::Rank, suit::Suit};
+
     #[test]
     fn new_deck_has_52_cards() {
         let deck = Deck::builder().build();
@@ -273,17 +501,84 @@ mod tests {
         // Checks if both decks are equal.
         assert_eq!(original_deck, shuffled_deck);
 
-        shuffled_deck.shuffle();
+        shuffled_deck.shuffle_with(&mut ChaCha8Rng::seed_from_u64(42));
 
         // Check that all cards are still present (just order changed)
-        assert_eq!(original_deck.len(), original_deck.len());
+        assert_eq!(shuffled_deck.len(), original_deck.len());
         assert!(original_deck
             .cards
             .iter()
-            .all(|card| original_deck.cards.contains(card)));
+            .all(|card| shuffled_deck.cards.contains(card)));
+
+        // The seed always gives the same order.
+        assert_eq!(
+            shuffled_deck.cards[..5],
+            [
+                Card::new(Suit::Clubs, Rank::King),
+                Card::new(Suit::Clubs, Rank::Three),
+                Card::new(Suit::Spades, Rank::Queen),
+                Card::new(Suit::Hearts, Rank::King),
+                Card::new(Suit::Clubs, Rank::Four),
+            ]
+        );
+    }
 
-        // After the shuffle the decks should have different orders.
-        assert_ne!(original_deck, shuffled_deck);
+    #[test]
+    fn iter_goes_from_the_top_of_the_deck() {
+        let mut deck = Deck::builder().seed(5).build();
+        let top: Vec<Card> = deck.iter().take(3).copied().collect();
+
+        assert_eq!(deck.iter().count(), 52);
+        assert_eq!(deck.draw(3), Ok(top));
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_order() {
+        let deck = Deck::builder().seed(7).build();
+        let same = Deck::builder().seed(7).build();
+        let other = Deck::builder().seed(8).build();
+
+        assert_eq!(deck.seed(), Some(7));
+        assert_eq!(deck, same);
+        assert_ne!(deck.cards, other.cards);
+        assert_ne!(deck.cards, Deck::builder().build().cards);
+    }
+
+    #[test]
+    fn shuffle_with_a_seeded_rng_is_reproducible() {
+        let mut deck = Deck::builder().build();
+        let mut other = deck.clone();
+
+        deck.shuffle_with(&mut ChaCha8Rng::seed_from_u64(3));
+        other.shuffle_with(&mut ChaCha8Rng::seed_from_u64(3));
+
+        assert_eq!(deck, other);
+    }
+
+    #[test]
+    fn shuffle_top_keeps_the_cards_below() {
+        let mut deck = Deck::builder().seed(2).build();
+        let original = deck.clone();
+
+        deck.shuffle_top_with(10, &mut ChaCha8Rng::seed_from_u64(3));
+
+        assert_ne!(deck.cards[..10], original.cards[..10]);
+        assert_eq!(deck.cards[10..], original.cards[10..]);
+        let mut top = deck.cards[..10].to_vec();
+        top.retain(|card| original.cards[..10].contains(card));
+        assert_eq!(top.len(), 10);
+    }
+
+    #[test]
+    fn reset_a_seeded_deck_restores_its_order() {
+        let mut deck = Deck::builder().seed(11).build();
+        let original = deck.clone();
+
+        deck.draw(5).expect("Enough cards in a full deck");
+        deck.shuffle();
+        deck.reset();
+
+        assert_eq!(deck, original);
     }
 
     #[test]
@@ -293,12 +588,13 @@ mod tests {
         let first = deck
             .cards
             .first()
-            .expect("It would be possible to get the first card on a fresh deck")
-            .clone();
+            .copied()
+            .expect("It would be possible to get the first card on a fresh deck");
 
         // Draw 1 cars.
         let drawn_card = deck
             .draw(1)
+            .expect("It should be possible to draw a card from a full deck")
             .first()
             .expect("It should be possible to draw a card from a full deck")
             .to_owned();
@@ -316,7 +612,7 @@ mod tests {
         let mut deck = Deck::builder().build();
 
         // Draw 10 cars.
-        let drawn_card = deck.draw(10);
+        let drawn_card = deck.draw(10).expect("Enough cards in a full deck");
 
         // The drawn card should be not contained in the deck anymore.
         assert!(drawn_card.iter().all(|card| !deck.cards.contains(card)));
@@ -328,7 +624,7 @@ mod tests {
         let mut deck = Deck::builder().build();
 
         // Draw 0 cars.
-        let drawn_card = deck.draw(0);
+        let drawn_card = deck.draw(0).expect("Drawing 0 cards never fails");
 
         // The array should contain 0 cards.
         assert_eq!(drawn_card.len(), 0);
@@ -339,7 +635,7 @@ mod tests {
     fn bottom_should_insert_card_at_the_end_of_the_deck() {
         let mut deck = Deck::builder().build();
         
-        let drawn_card = deck.draw(4);
+        let drawn_card = deck.draw(4).expect("Enough cards in a full deck");
 
         deck.bottom(&mut drawn_card.clone());
 
@@ -348,15 +644,129 @@ mod tests {
         assert_eq!(bottom_cards, drawn_card);
     }
 
-    // #[test]
-    // fn test_draw_too_many() {
-    //     let mut deck = Deck::new(None);
-    //     assert!(matches!(
-    //         deck.draw(53),
-    //         Err(DeckError::NotEnoughCards {
-    //             requested: 53,
-    //             available: 52
-    //         })
-    //     ));
-    // }
+    #[test]
+    fn test_draw_too_many() {
+        let mut deck = Deck::builder().build();
+        assert!(matches!(
+            deck.draw(53),
+            Err(DeckError::NotEnoughCards {
+                requested: 53,
+                available: 52
+            })
+        ));
+        // A failed draw leaves the deck untouched.
+        assert_eq!(deck.len(), 52);
+    }
+
+    #[test]
+    fn draw_up_to_returns_the_remaining_cards() {
+        let mut deck = Deck::builder().build();
+        deck.draw(50).expect("Enough cards in a full deck");
+
+        assert_eq!(deck.draw_up_to(3).len(), 2);
+        assert!(deck.is_empty());
+        assert!(deck.draw_up_to(3).is_empty());
+    }
+
+    #[test]
+    fn pull_a_specific_card_from_the_deck() {
+        let card = Card::new(Suit::Hearts, Rank::Seven);
+        let mut deck = Deck::builder().build();
+
+        assert_eq!(deck.pull(&card), Ok(card));
+        assert!(!deck.contains(&card));
+        assert_eq!(deck.len(), 51);
+
+        assert_eq!(deck.pull(&card), Err(DeckError::CardNotFound(card)));
+    }
+
+    #[test]
+    fn pull_a_banned_card_fails() {
+        let banned = Card::new(Suit::Spades, Rank::Ace);
+        let mut deck = Deck::builder().ban_card(banned).build();
+
+        assert_eq!(deck.pull(&banned), Err(DeckError::BannedCard(banned)));
+    }
+
+    #[test]
+    fn contains_follows_draws_and_bottoms() {
+        let mut deck = Deck::builder().seed(7).build();
+        let mut drawn = deck.draw(5).unwrap();
+
+        assert!(drawn.iter().all(|card| !deck.contains(card)));
+        assert!(deck.iter().all(|card| deck.contains(card)));
+
+        deck.bottom(&mut drawn);
+        assert_eq!(deck.len(), 52);
+        assert!(CardSet::full().iter().all(|card| deck.contains(&card)));
+
+        deck.reset();
+        assert!(CardSet::full().iter().all(|card| deck.contains(&card)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deck_serializes_its_cards_banned_cards_and_seed() {
+        let mut deck = Deck::builder()
+            .ban_card(Card::new(Suit::Hearts, Rank::Ace))
+            .seed(3)
+            .build();
+        let drawn = deck.draw(40).expect("Enough cards in a full deck");
+        let json = serde_json::to_string(&deck).expect("A deck serializes");
+        let codes: Vec<String> = deck
+            .iter()
+            .map(|card| format!("\"{}{}\"", card.rank(), card.suit().letter()))
+            .collect();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"cards":[{}],"banned_cards":["AH"],"seed":3}}"#,
+                codes.join(",")
+            )
+        );
+
+        let mut loaded: Deck = serde_json::from_str(&json).expect("A valid deck");
+        assert_eq!(loaded, deck);
+        assert_eq!(loaded.len(), 11);
+
+        // The drawn cards are not banned: they go back in the deck.
+        loaded.bottom(&mut vec![drawn[0]]);
+        assert!(loaded.contains(&drawn[0]));
+        loaded.reset();
+        assert_eq!(
+            loaded,
+            Deck::builder()
+                .ban_card(Card::new(Suit::Hearts, Rank::Ace))
+                .seed(3)
+                .build()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deck_with_a_card_twice_or_banned_does_not_deserialize() {
+        assert!(serde_json::from_str::<Deck>(r#"{"cards":["QC","10H","QC"]}"#).is_err());
+        assert!(serde_json::from_str::<Deck>(r#"{"cards":["QC","1H"]}"#).is_err());
+        assert!(serde_json::from_str::<Deck>(r#"{"cards":["QC"],"banned_cards":["QC"]}"#).is_err());
+        let deck: Deck = serde_json::from_str(r#"{"cards":["QC"]}"#).expect("A valid deck");
+        assert_eq!(deck.len(), 1);
+        assert_eq!(deck.seed(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deck_builder_serializes_its_configuration() {
+        let builder = Deck::builder()
+            .ban_card(Card::new(Suit::Hearts, Rank::King))
+            .ban_card(Card::new(Suit::Spades, Rank::Ace))
+            .seed(42);
+        let json = serde_json::to_string(&builder).expect("A builder serializes");
+        assert_eq!(json, r#"{"banned_cards":["AS","KH"],"seed":42}"#);
+
+        let loaded: DeckBuilder = serde_json::from_str(&json).expect("A valid builder");
+        assert_eq!(loaded.build(), builder.build());
+
+        let loaded: DeckBuilder = serde_json::from_str(r#"{"seed":7}"#).expect("A valid builder");
+        assert_eq!(loaded.build(), Deck::builder().seed(7).build());
+    }
 }
@@ -0,0 +1,408 @@
+//! Journal of a Scoundrel game and its exact replay.
+//!
+//! Every mutation of a `Scoundrel` game is recorded as an `Event`.
+//! Together with the deck seed and the rules, the journal rebuilds
+//! the game step by step with `Replay`.
+
+use core::fmt;
+
+use crate::{
+    card::Card,
+    rules::ScoundrelRules,
+    scoundrel::{Action, CombatMode, GameState, Scoundrel, ScoundrelError, Weapon},
+};
+
+/// A mutation of a Scoundrel game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A room has been entered.
+    RoomEntered {
+        /// The cards carried over from the previous room, if any.
+        carried: Vec<Card>,
+        /// The cards drawn from the deck to fill the room.
+        drawn: Vec<Card>,
+    },
+    /// A card of the room has been played.
+    CardPlayed {
+        /// The card played.
+        card: Card,
+        /// How the monster was fought, `None` for weapons and potions.
+        combat: Option<CombatMode>,
+        /// Life points lost.
+        damage: u8,
+        /// Life points healed, 0 for a wasted potion.
+        heal: u8,
+    },
+    /// A weapon has been equipped.
+    WeaponEquipped {
+        /// The weapon equipped.
+        weapon: Card,
+        /// The previous weapon, discarded with its monster stack.
+        discarded: Option<Weapon>,
+    },
+    /// A potion has been discarded without healing: the room had already
+    /// used up its potions.
+    PotionWasted {
+        /// The potion discarded.
+        potion: Card,
+    },
+    /// A monster has been slain, the character survived the fight.
+    MonsterSlain {
+        /// The monster slain.
+        monster: Card,
+        /// The weapon used, `None` if fought barehanded.
+        weapon: Option<Card>,
+    },
+    /// The character ran away, the cards of the room went under the deck.
+    Fled {
+        /// The cards of the room, in the order they went under the deck.
+        cards: Vec<Card>,
+    },
+    /// The game is over.
+    GameOver {
+        /// Whether the game was won or lost.
+        state: GameState,
+    },
+}
+
+impl Event {
+    /// Returns the player action that produces the event, `None` for the
+    /// consequences of an action (weapon equipped, potion wasted, monster
+    /// slain, game over).
+    ///
+    /// `RoomEntered` is returned as `Action::EnterRoom`, even when the room
+    /// was entered automatically after clearing the previous one.
+    pub fn action(&self) -> Option<Action> {
+        match self {
+            Event::RoomEntered { .. } => Some(Action::EnterRoom),
+            Event::CardPlayed {
+                card,
+                combat: Some(mode),
+                ..
+            } => Some(Action::Fight(*card, *mode)),
+            Event::CardPlayed {
+                card, combat: None, ..
+            } => Some(Action::PlayCard(*card)),
+            Event::Fled { .. } => Some(Action::RunAway),
+            Event::WeaponEquipped { .. }
+            | Event::PotionWasted { .. }
+            | Event::MonsterSlain { .. }
+            | Event::GameOver { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::RoomEntered { drawn, .. } => {
+                write!(f, "entered a room, drawing {} cards", drawn.len())
+            }
+            Event::CardPlayed {
+                card, damage, heal, ..
+            } => write!(f, "played {card}: -{damage} +{heal} life points"),
+            Event::WeaponEquipped { weapon, .. } => write!(f, "equipped {weapon}"),
+            Event::PotionWasted { potion } => write!(f, "wasted {potion}"),
+            Event::MonsterSlain { monster, .. } => write!(f, "slayed {monster}"),
+            Event::Fled { .. } => write!(f, "ran away"),
+            Event::GameOver { state } => write!(f, "game over: {state:?}"),
+        }
+    }
+}
+
+/// Errors returned while replaying a journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The journal holds an event that is not a player action at this position,
+    /// or the game produced different events than the journal.
+    Diverged {
+        /// Position of the first event that doesn't match.
+        position: usize,
+    },
+    /// The action of the journal is illegal in the replayed game.
+    IllegalAction {
+        /// Position of the event of the action.
+        position: usize,
+        /// Why the action was rejected.
+        error: ScoundrelError,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Diverged { position } => {
+                write!(
+                    f,
+                    "the replay diverged from the journal at event {position}"
+                )
+            }
+            ReplayError::IllegalAction { position, error } => {
+                write!(f, "illegal action at event {position}: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Rebuilds a game step by step from its seed, rules and journal.
+///
+/// # Examples
+/// ```
+/// use dungeon_and_cards::{Replay, Scoundrel};
+///
+/// let mut game = Scoundrel::from_seed(42);
+/// game.enter_room().unwrap();
+/// game.run_away().unwrap();
+///
+/// let replayed = Replay::from_game(&game).run().unwrap();
+/// assert_eq!(replayed.journal(), game.journal());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Replay {
+    /// The game being rebuilt.
+    game: Scoundrel,
+    /// The journal to replay.
+    journal: Vec<Event>,
+    /// Number of events of the journal already replayed.
+    position: usize,
+}
+
+impl Replay {
+    /// Prepares the replay of `journal` on a new game dealt with `seed`
+    /// and played with `rules`.
+    pub fn with_rules(seed: u64, rules: ScoundrelRules, journal: Vec<Event>) -> Self {
+        Self::with_game(Scoundrel::from_seed_with_rules(seed, rules), journal)
+    }
+
+    /// Prepares the replay of a game from its beginning, with its seed and
+    /// its rules.
+    pub fn from_game(game: &Scoundrel) -> Self {
+        Self::with_rules(game.seed(), game.rules().clone(), game.journal().to_vec())
+    }
+
+    fn with_game(game: Scoundrel, journal: Vec<Event>) -> Self {
+        Self {
+            game,
+            journal,
+            position: 0,
+        }
+    }
+
+    /// Returns the game as rebuilt so far.
+    pub fn game(&self) -> &Scoundrel {
+        &self.game
+    }
+
+    /// Returns `true` once the whole journal has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.journal.len()
+    }
+
+    /// Returns the next action to replay, `None` once finished.
+    pub fn next_action(&self) -> Option<Action> {
+        self.journal.get(self.position).and_then(Event::action)
+    }
+
+    /// Replays the next action of the journal, checking that the game
+    /// produces exactly the recorded events.
+    ///
+    /// # Returns
+    /// The action replayed, `None` once the whole journal has been replayed.
+    ///
+    /// # Errors
+    /// - `ReplayError::Diverged` if the game doesn't match the journal
+    /// - `ReplayError::IllegalAction` if the recorded action is not allowed
+    pub fn step(&mut self) -> Result<Option<Action>, ReplayError> {
+        if self.is_finished() {
+            return Ok(None);
+        }
+
+        let position = self.position;
+        let action = self
+            .next_action()
+            .ok_or(ReplayError::Diverged { position })?;
+        self.game
+            .apply(action)
+            .map_err(|error| ReplayError::IllegalAction { position, error })?;
+
+        // The action may trigger more events, e.g. entering the next room.
+        let produced = &self.game.journal()[position..];
+        let expected = self.journal.get(position..position + produced.len());
+        if expected != Some(produced) {
+            let mismatch = produced
+                .iter()
+                .zip(&self.journal[position..])
+                .take_while(|(produced, expected)| produced == expected)
+                .count();
+            return Err(ReplayError::Diverged {
+                position: position + mismatch,
+            });
+        }
+
+        self.position += produced.len();
+        Ok(Some(action))
+    }
+
+    /// Replays the whole journal, returning the rebuilt game.
+    ///
+    /// # Errors
+    /// The first error returned by `step`.
+    pub fn run(mut self) -> Result<Scoundrel, ReplayError> {
+        while self.step()?.is_some() {}
+        Ok(self.game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rank::Rank, suit::Suit};
+
+    /// Plays a seeded game with the first legal action until it is over,
+    /// running away whenever possible.
+    fn played_game(seed: u64) -> Scoundrel {
+        let mut game = Scoundrel::from_seed(seed);
+        while game.state() == GameState::InGame {
+            let actions = game.legal_actions();
+            let action = actions
+                .iter()
+                .find(|action| **action == Action::RunAway)
+                .unwrap_or(&actions[0]);
+            game.apply(*action).expect("The action is legal");
+        }
+        game
+    }
+
+    #[test]
+    fn first_room_is_journaled() {
+        let mut game = Scoundrel::from_seed(3);
+        game.enter_room().expect("First room");
+
+        assert_eq!(
+            game.journal(),
+            &[Event::RoomEntered {
+                carried: Vec::new(),
+                drawn: game.room().to_vec()
+            }]
+        );
+    }
+
+    #[test]
+    fn fights_are_journaled_with_their_damage() {
+        let mut game = Scoundrel::from_seed(3);
+        game.enter_room().expect("First room");
+        let monster = game
+            .room()
+            .iter()
+            .copied()
+            .find(|card| matches!(card.suit(), Suit::Spades | Suit::Clubs))
+            .expect("A monster in the first room");
+        let life_points = game.life_points();
+
+        game.apply(Action::Fight(monster, CombatMode::Barehanded))
+            .expect("The monster is in the room");
+
+        assert_eq!(
+            &game.journal()[1..3],
+            &[
+                Event::CardPlayed {
+                    card: monster,
+                    combat: Some(CombatMode::Barehanded),
+                    damage: life_points - game.life_points(),
+                    heal: 0
+                },
+                Event::MonsterSlain {
+                    monster,
+                    weapon: None
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_rebuilds_the_same_game() {
+        for seed in 0..20 {
+            let game = played_game(seed);
+            let replayed = Replay::from_game(&game)
+                .run()
+                .expect("The journal is valid");
+
+            assert_eq!(replayed.journal(), game.journal());
+            assert_eq!(replayed.state(), game.state());
+            assert_eq!(replayed.life_points(), game.life_points());
+            assert_eq!(replayed.room(), game.room());
+            assert_eq!(replayed.score(), game.score());
+            assert!(matches!(
+                game.journal().last(),
+                Some(Event::GameOver { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn replay_goes_step_by_step() {
+        let game = played_game(5);
+        let mut replay = Replay::from_game(&game);
+
+        assert_eq!(replay.step(), Ok(Some(Action::EnterRoom)));
+        assert_eq!(replay.game().rooms_visited(), 1);
+        assert!(!replay.is_finished());
+
+        while replay.step().expect("The journal is valid").is_some() {}
+        assert!(replay.is_finished());
+        assert_eq!(replay.step(), Ok(None));
+    }
+
+    #[test]
+    fn replay_follows_the_rules_of_the_game() {
+        let mut banned_cards = ScoundrelRules::default().banned_cards();
+        banned_cards.insert(Card::new(Suit::Clubs, Rank::Two));
+        let rules = ScoundrelRules::builder()
+            .room_size(5)
+            .potions_per_room(2)
+            .banned_cards(banned_cards)
+            .build()
+            .expect("Valid rules");
+        let mut game = Scoundrel::from_seed_with_rules(7, rules.clone());
+        while game.state() == GameState::InGame {
+            let action = game.legal_actions()[0];
+            game.apply(action).expect("The action is legal");
+        }
+
+        let replayed = Replay::with_rules(7, rules, game.journal().to_vec())
+            .run()
+            .expect("The journal is valid");
+        assert_eq!(replayed.journal(), game.journal());
+        assert_eq!(replayed.score(), game.score());
+
+        // With the default rules, the rooms are dealt differently.
+        let replay = Replay::with_rules(7, ScoundrelRules::default(), game.journal().to_vec());
+        assert!(replay.run().is_err());
+    }
+
+    #[test]
+    fn replay_with_another_seed_diverges() {
+        let game = played_game(5);
+        let replay = Replay::with_rules(6, ScoundrelRules::default(), game.journal().to_vec());
+
+        assert!(replay.run().is_err());
+    }
+
+    #[test]
+    fn replay_rejects_a_tampered_journal() {
+        let game = played_game(5);
+        let mut journal = game.journal().to_vec();
+        journal[0] = Event::RoomEntered {
+            carried: Vec::new(),
+            drawn: vec![Card::new(Suit::Hearts, Rank::Two)],
+        };
+
+        let replay = Replay::with_rules(5, ScoundrelRules::default(), journal);
+        assert_eq!(
+            replay.run().err(),
+            Some(ReplayError::Diverged { position: 0 })
+        );
+    }
+}
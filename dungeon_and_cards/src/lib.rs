@@ -0,0 +1,49 @@
+//! Dungeon and Cards: the Scoundrel solitaire card game.
+//!
+//! - Cards: `Card`, `Suit`, `Rank`, `CardSet` and `Deck`, built with a
+//!   `DeckBuilder`.
+//! - The game engine: `Scoundrel`, played with `Action`s, under
+//!   `ScoundrelRules`. Every game is journaled and can be replayed with
+//!   `Replay`, or saved with the `save` feature.
+//! - Bots and analysis: the `Strategy` bots, the batch `simulate` runs and
+//!   the exhaustive `solve`r.
+//!
+//! # Examples
+//! ```
+//! use dungeon_and_cards::{play, GameState, GreedyBot, Scoundrel};
+//!
+//! let mut game = Scoundrel::from_seed(42);
+//! let result = play(&mut game, &mut GreedyBot::default());
+//! assert_ne!(result.state, GameState::InGame);
+//! ```
+
+mod card;
+mod card_set;
+mod deck;
+mod journal;
+mod rank;
+mod rules;
+#[cfg(feature = "save")]
+mod save;
+mod scoundrel;
+mod simulation;
+mod solver;
+mod strategy;
+mod suit;
+
+pub use card::{Card, CardIndexError, ParseCardError};
+pub use card_set::{CardSet, CardSetIter};
+pub use deck::{Deck, DeckBuilder, DeckError};
+pub use journal::{Event, Replay, ReplayError};
+pub use rank::{Rank, RankMode};
+pub use rules::{RulesError, ScoundrelRules, ScoundrelRulesBuilder};
+#[cfg(feature = "save")]
+pub use save::{SaveError, SAVE_VERSION};
+pub use scoundrel::{
+    Action, CombatMode, CombatPreview, GameResult, GameState, RoomRecord, RoomStatus, Scoundrel,
+    ScoundrelError, Weapon,
+};
+pub use simulation::{simulate, simulate_parallel, Csv, Statistics};
+pub use solver::{solve, solve_within, Solution};
+pub use strategy::{play, GameView, GreedyBot, MonteCarloBot, RandomBot, Strategy};
+pub use suit::Suit;
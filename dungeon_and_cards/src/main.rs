@@ -1,9 +1,749 @@
-mod card;
-mod deck;
-mod rank;
-mod scoundrel;
-mod suit;
+use std::{
+    io::{self, BufRead, Write},
+    num::NonZeroUsize,
+};
+
+use dungeon_and_cards::{
+    Action, Card, CombatMode, Event, GameState, GameView, GreedyBot, MonteCarloBot, RandomBot,
+    RankMode, RoomStatus, Scoundrel, ScoundrelRules, Strategy, Suit,
+};
+
+/// Positions `--solve` searches at most, unless `--max-positions` is given:
+/// about 1 GB of memory.
+const MAX_POSITIONS: usize = 10_000_000;
+
+/// A command typed by the player at the prompt.
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    /// Play the card at the given (0-based) position in the room.
+    Play(usize),
+    /// Fight the monster at the given (0-based) position barehanded.
+    PlayBarehanded(usize),
+    /// Run away from the current room.
+    RunAway,
+    /// Take back the last move.
+    Undo,
+    /// Play again the last move taken back.
+    Redo,
+    /// Print the rooms visited so far.
+    History,
+    /// Save the game to the given file.
+    Save(String),
+    /// Print the list of commands.
+    Help,
+    /// Leave the game.
+    Quit,
+}
+
+impl Command {
+    /// Parses a line of user input into a command.
+    ///
+    /// Cards are picked with their 1-based position in the room,
+    /// prefixed with `b` to fight a monster barehanded.
+    fn parse(input: &str) -> Option<Self> {
+        if let Some(path) = input.trim().strip_prefix("save ") {
+            return Some(Command::Save(path.trim().to_string()));
+        }
+        match input.trim().to_lowercase().as_str() {
+            "r" | "run" => Some(Command::RunAway),
+            "u" | "undo" => Some(Command::Undo),
+            "redo" => Some(Command::Redo),
+            "l" | "log" | "history" => Some(Command::History),
+            "h" | "help" | "?" => Some(Command::Help),
+            "q" | "quit" | "exit" => Some(Command::Quit),
+            other => match other.strip_prefix('b') {
+                Some(position) => parse_position(position).map(Command::PlayBarehanded),
+                None => parse_position(other).map(Command::Play),
+            },
+        }
+    }
+}
+
+/// A bot that can play in place of the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bot {
+    Random,
+    Greedy,
+    MonteCarlo,
+}
+
+impl Bot {
+    /// Parses the name of a bot: `random`, `greedy` or `monte-carlo`.
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "random" => Some(Bot::Random),
+            "greedy" => Some(Bot::Greedy),
+            "monte-carlo" | "mc" => Some(Bot::MonteCarlo),
+            _ => None,
+        }
+    }
+
+    /// Creates the strategy of the bot, its random choices determined by `seed`.
+    fn strategy(self, seed: u64) -> Box<dyn Strategy> {
+        match self {
+            Bot::Random => Box::new(RandomBot::new(seed)),
+            Bot::Greedy => Box::new(GreedyBot::default()),
+            Bot::MonteCarlo => Box::new(MonteCarloBot::new(seed)),
+        }
+    }
+}
+
+/// Parses a 1-based card position into a 0-based index.
+fn parse_position(input: &str) -> Option<usize> {
+    input
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(|n| n - 1)
+}
+
+/// Short description of what a card does when played.
+fn describe_card(game: &Scoundrel, card: &Card) -> String {
+    match card.suit() {
+        Suit::Spades | Suit::Clubs => match game.combat_preview(card) {
+            Some(preview) => match preview.weapon {
+                Some(damage) => format!(
+                    "monster, {damage} damage with weapon, {} barehanded",
+                    preview.barehanded
+                ),
+                None => format!("monster, {} damage", preview.barehanded),
+            },
+            None => "monster".to_string(),
+        },
+        Suit::Diamonds => "weapon".to_string(),
+        Suit::Hearts if game.potions_used_in_room() >= game.rules().potions_per_room() => {
+            "potion, no effect: no more potion can heal in this room".to_string()
+        }
+        Suit::Hearts => "potion".to_string(),
+    }
+}
+
+fn print_help() {
+    println!("Commands:");
+    println!("  1-4        play the card at that position in the room");
+    println!("  b1-b4      fight the monster at that position barehanded");
+    println!("  r, run     run away from the room (not twice in a row)");
+    println!("  u, undo    take back the last move (not in ranked games)");
+    println!("  redo       play again the last move taken back");
+    println!("  l, log     show the rooms visited so far");
+    println!("  save FILE  save the game, resume it with `--load FILE`");
+    println!("  h, help    show this help");
+    println!("  q, quit    leave the game");
+}
+
+fn print_history(game: &Scoundrel) {
+    for (number, room) in game.room_history().iter().enumerate() {
+        let cards: Vec<String> = room.cards().iter().map(Card::to_string).collect();
+        let played: Vec<String> = room.played().iter().map(Card::to_string).collect();
+        let status = match room.status() {
+            RoomStatus::Entered => "current",
+            RoomStatus::Fled => "fled",
+            RoomStatus::Cleared => "cleared",
+        };
+        println!(
+            "  Room {} ({status}): {} | played: {}",
+            number + 1,
+            cards.join(", "),
+            played.join(", ")
+        );
+    }
+}
+
+fn print_status(game: &Scoundrel) {
+    println!();
+    println!(
+        "== Room {} == Life: {}/{} == Cards left in the dungeon: {} (~{} rooms) ==",
+        game.rooms_visited(),
+        game.life_points(),
+        game.rules().max_life(),
+        game.cards_left(),
+        game.rooms_remaining()
+    );
+
+    match game.weapon_equipped() {
+        Some(weapon) => {
+            let monsters: Vec<String> = weapon
+                .defeated_monsters()
+                .iter()
+                .map(Card::to_string)
+                .collect();
+            if monsters.is_empty() {
+                println!("Weapon: {}", weapon.weapon());
+            } else {
+                println!(
+                    "Weapon: {} | Slain: {}",
+                    weapon.weapon(),
+                    monsters.join(", ")
+                );
+            }
+        }
+        None => println!("Weapon: none (barehanded)"),
+    }
+
+    if game.is_legal(&Action::RunAway) {
+        println!("Room (you can run away):");
+    } else {
+        println!("Room:");
+    }
+    for (position, card) in game.room().iter().enumerate() {
+        println!(
+            "  [{}] {}  ({})",
+            position + 1,
+            card,
+            describe_card(game, card)
+        );
+    }
+}
+
+/// Plays the card at `position` in the room.
+///
+/// Monsters are fought with the weapon when it can be used, unless
+/// `CombatMode::Barehanded` is given.
+fn play_card_at(
+    game: &mut Scoundrel,
+    position: usize,
+    mode: Option<CombatMode>,
+) -> Result<GameState, String> {
+    let Some(card) = game.room().get(position).copied() else {
+        return Err(format!("There is no card at position {}", position + 1));
+    };
+    let events = game.journal().len();
+    let state = match mode {
+        Some(mode) => game.play_card_with(&card, mode),
+        None => game.play_card(&card),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let wasted = game.journal()[events..]
+        .iter()
+        .any(|event| matches!(event, Event::PotionWasted { .. }));
+    if wasted {
+        println!(
+            "{card} was wasted: only {} potion per room can heal.",
+            game.rules().potions_per_room()
+        );
+    }
+    Ok(state)
+}
+
+/// Runs away from the room and enters the next one.
+fn run_away(game: &mut Scoundrel) -> Result<GameState, String> {
+    game.apply(Action::RunAway).map_err(|e| e.to_string())?;
+    println!("You ran away!");
+    game.apply(Action::EnterRoom).map_err(|e| e.to_string())
+}
+
+/// Takes back the last move.
+///
+/// Running away and entering the next room are taken back together,
+/// and the first room, entered for the player, can't be taken back.
+fn undo_move(game: &mut Scoundrel) -> Result<GameState, String> {
+    game.undo().map_err(|e| e.to_string())?;
+    if game.room().is_empty() {
+        if !game.can_undo() {
+            game.redo().map_err(|e| e.to_string())?;
+            return Err("There is nothing to undo".to_string());
+        }
+        game.undo().map_err(|e| e.to_string())?;
+    }
+    println!("Move taken back.");
+    Ok(game.state())
+}
+
+/// Plays again the last move taken back, see `undo_move`.
+fn redo_move(game: &mut Scoundrel) -> Result<GameState, String> {
+    game.redo().map_err(|e| e.to_string())?;
+    if game.room().is_empty() && game.can_redo() {
+        game.redo().map_err(|e| e.to_string())?;
+    }
+    Ok(game.state())
+}
+
+/// Saves the game to the file at `path`.
+#[cfg(feature = "save")]
+fn save_game(game: &Scoundrel, path: &str) -> Result<(), String> {
+    std::fs::write(path, game.to_save()).map_err(|e| format!("Unable to save to {path}: {e}"))?;
+    println!("Game saved to {path}.");
+    Ok(())
+}
+
+#[cfg(not(feature = "save"))]
+fn save_game(_game: &Scoundrel, _path: &str) -> Result<(), String> {
+    Err("Saving needs the game built with the `save` feature".to_string())
+}
+
+/// Loads the game saved in the file at `path`.
+#[cfg(feature = "save")]
+fn load_game(path: &str) -> Result<Scoundrel, String> {
+    let save = std::fs::read_to_string(path).map_err(|e| format!("Unable to read {path}: {e}"))?;
+    Scoundrel::from_save(&save).map_err(|e| format!("Unable to load {path}: {e}"))
+}
+
+#[cfg(not(feature = "save"))]
+fn load_game(_path: &str) -> Result<Scoundrel, String> {
+    Err("Loading needs the game built with the `save` feature".to_string())
+}
+
+/// Prints the best line of play from the current position of the game,
+/// searching at most `max_positions` positions.
+fn print_solution(game: &Scoundrel, max_positions: usize) {
+    println!("Solving seed {}...", game.seed());
+    let Some(solution) = dungeon_and_cards::solve_within(game, max_positions) else {
+        println!(
+            "Unknown: the search gave up after {max_positions} positions, raise --max-positions to search further."
+        );
+        return;
+    };
+    for (number, action) in solution.line.iter().enumerate() {
+        println!("  {:>3}. {action}", number + 1);
+    }
+    if solution.winnable {
+        println!("The deal can be won.");
+    } else {
+        println!("The deal can't be won.");
+    }
+    println!(
+        "Best score: {} ({} positions searched)",
+        solution.best_score, solution.positions
+    );
+}
+
+/// Options given on the command line.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Options {
+    /// Seed of the deal, random if not given.
+    seed: Option<u64>,
+    /// Aces are worth 1 instead of 14.
+    aces_low: bool,
+    /// Ranked game: moves can't be taken back.
+    ranked: bool,
+    /// Save file of the game to resume.
+    load: Option<String>,
+    /// Print the best line of play of the deal instead of playing it.
+    solve: bool,
+    /// Bot playing the game in place of the player.
+    bot: Option<Bot>,
+    /// Positions the solver searches at most, `MAX_POSITIONS` if not given.
+    max_positions: Option<usize>,
+}
+
+impl Options {
+    /// Parses `--seed <u64>`, `--aces-low`, `--ranked`, `--load <file>`,
+    /// `--solve`, `--max-positions <n>` and `--bot <name>` from the command
+    /// line arguments.
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--aces-low" => options.aces_low = true,
+                "--ranked" => options.ranked = true,
+                "--solve" => options.solve = true,
+                "--seed" => {
+                    let seed = args.next().ok_or("--seed needs a value")?;
+                    let seed = seed.parse().map_err(|_| format!("Invalid seed `{seed}`"))?;
+                    options.seed = Some(seed);
+                }
+                "--max-positions" => {
+                    let positions = args.next().ok_or("--max-positions needs a value")?;
+                    let positions = positions
+                        .parse()
+                        .map_err(|_| format!("Invalid number of positions `{positions}`"))?;
+                    options.max_positions = Some(positions);
+                }
+                "--load" => options.load = Some(args.next().ok_or("--load needs a file")?),
+                "--bot" => {
+                    let name = args.next().ok_or("--bot needs a name")?;
+                    let bot = Bot::parse(&name).ok_or(format!(
+                        "Unknown bot `{name}`, expected random, greedy or monte-carlo"
+                    ))?;
+                    options.bot = Some(bot);
+                }
+                other => return Err(format!("Unknown argument `{other}`")),
+            }
+        }
+        // A save holds its own deal and rules.
+        if options.load.is_some() && (options.seed.is_some() || options.aces_low) {
+            return Err(
+                "--seed and --aces-low can't be used with --load, the save holds the deal and the rules"
+                    .to_string(),
+            );
+        }
+        if options.max_positions.is_some() && !options.solve {
+            return Err("--max-positions only applies to --solve".to_string());
+        }
+        Ok(options)
+    }
+}
+
+/// Options of the `simulate` subcommand, playing many games with a bot.
+#[derive(Debug, PartialEq, Eq)]
+struct SimulateOptions {
+    /// Number of games to play.
+    games: u64,
+    /// Seed of the first deal, the next games play the following seeds.
+    first_seed: u64,
+    /// Bot playing the games.
+    bot: Bot,
+    /// Aces are worth 1 instead of 14.
+    aces_low: bool,
+    /// Print the statistics as CSV instead of a table.
+    csv: bool,
+    /// Number of threads playing the games, all the cores if not given.
+    threads: Option<NonZeroUsize>,
+}
+
+impl Default for SimulateOptions {
+    /// 1000 games from seed 0, played by the greedy bot on all the cores.
+    fn default() -> Self {
+        Self {
+            games: 1000,
+            first_seed: 0,
+            bot: Bot::Greedy,
+            aces_low: false,
+            csv: false,
+            threads: None,
+        }
+    }
+}
+
+impl SimulateOptions {
+    /// Parses `--games <u64>`, `--seed <u64>`, `--bot <name>`, `--aces-low`,
+    /// `--csv` and `--threads <n>` from the arguments following `simulate`.
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = SimulateOptions::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--aces-low" => options.aces_low = true,
+                "--csv" => options.csv = true,
+                "--games" => {
+                    let games = args.next().ok_or("--games needs a value")?;
+                    options.games = games
+                        .parse()
+                        .map_err(|_| format!("Invalid number of games `{games}`"))?;
+                }
+                "--seed" => {
+                    let seed = args.next().ok_or("--seed needs a value")?;
+                    options.first_seed =
+                        seed.parse().map_err(|_| format!("Invalid seed `{seed}`"))?;
+                }
+                "--threads" => {
+                    let threads = args.next().ok_or("--threads needs a value")?;
+                    let threads = threads
+                        .parse()
+                        .map_err(|_| format!("Invalid number of threads `{threads}`"))?;
+                    options.threads = Some(threads);
+                }
+                "--bot" => {
+                    let name = args.next().ok_or("--bot needs a name")?;
+                    options.bot = Bot::parse(&name).ok_or(format!(
+                        "Unknown bot `{name}`, expected random, greedy or monte-carlo"
+                    ))?;
+                }
+                other => return Err(format!("Unknown argument `{other}`")),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Returns the seeds of the deals to play.
+    fn seeds(&self) -> std::ops::Range<u64> {
+        self.first_seed..self.first_seed.saturating_add(self.games)
+    }
+}
+
+/// Plays the games of the `simulate` subcommand and prints their statistics.
+fn simulate(args: impl IntoIterator<Item = String>) {
+    let options = match SimulateOptions::parse(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}.");
+            eprintln!(
+                "Usage: dungeon_and_cards simulate [--games <u64>] [--seed <u64>] [--bot <name>] [--aces-low] [--csv] [--threads <n>]"
+            );
+            std::process::exit(2);
+        }
+    };
+
+    let rank_mode = if options.aces_low {
+        RankMode::AceLow
+    } else {
+        RankMode::AceHigh
+    };
+    let rules = ScoundrelRules::builder()
+        .rank_mode(rank_mode)
+        .build()
+        .expect("The standard rules are valid");
+    let threads = options
+        .threads
+        .unwrap_or_else(|| std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN));
+    let statistics =
+        dungeon_and_cards::simulate_parallel(options.seeds(), &rules, threads, |seed| {
+            options.bot.strategy(seed)
+        });
+    if options.csv {
+        print!("{}", statistics.csv());
+    } else {
+        print!("{statistics}");
+    }
+}
 
 fn main() {
-    println!("Hello, world!");
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("simulate") {
+        args.next();
+        simulate(args);
+        return;
+    }
+
+    let options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}.");
+            eprintln!(
+                "Usage: dungeon_and_cards [--seed <u64>] [--aces-low] [--ranked] [--load <file>] [--solve [--max-positions <n>]] [--bot <name>]"
+            );
+            eprintln!(
+                "       dungeon_and_cards simulate [--games <u64>] [--seed <u64>] [--bot <name>] [--aces-low] [--csv] [--threads <n>]"
+            );
+            std::process::exit(2);
+        }
+    };
+
+    // Aces are the strongest monsters, unless asked otherwise.
+    let rank_mode = if options.aces_low {
+        RankMode::AceLow
+    } else {
+        RankMode::AceHigh
+    };
+    let mut game = match (&options.load, options.seed) {
+        (Some(path), _) => match load_game(path) {
+            Ok(game) => game,
+            Err(message) => {
+                eprintln!("{message}.");
+                std::process::exit(1);
+            }
+        },
+        (None, Some(seed)) => Scoundrel::from_seed_with_rank_mode(seed, rank_mode),
+        (None, None) => Scoundrel::with_rank_mode(rank_mode),
+    };
+    if options.ranked {
+        game = game.without_undo();
+    }
+    if options.solve {
+        print_solution(&game, options.max_positions.unwrap_or(MAX_POSITIONS));
+        return;
+    }
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    println!("Welcome to Scoundrel! Type `h` for help.");
+    match game.rank_mode() {
+        RankMode::AceLow => println!("Aces are low: they are worth 1."),
+        RankMode::AceHigh => println!("Aces are high: they are worth 14."),
+    }
+    println!(
+        "Seed: {} (replay with `--seed {}`)",
+        game.seed(),
+        game.seed()
+    );
+    if game.rooms_visited() == 0 {
+        game.enter_room()
+            .expect("A new game can always enter its first room");
+    }
+
+    let mut bot = options.bot.map(|bot| bot.strategy(game.seed()));
+
+    while game.state() == GameState::InGame {
+        print_status(&game);
+        if let Some(bot) = &mut bot {
+            let action = bot.choose(GameView::new(&game));
+            println!("> {action}");
+            game.apply(action).expect("Bots only choose legal actions");
+            continue;
+        }
+        print!("> ");
+        io::stdout().flush().expect("Unable to flush stdout");
+
+        let Some(Ok(line)) = lines.next() else {
+            // End of input: leave the game.
+            println!();
+            return;
+        };
+
+        let result = match Command::parse(&line) {
+            Some(Command::Play(position)) => play_card_at(&mut game, position, None),
+            Some(Command::PlayBarehanded(position)) => {
+                play_card_at(&mut game, position, Some(CombatMode::Barehanded))
+            }
+            Some(Command::RunAway) => run_away(&mut game),
+            Some(Command::Undo) => undo_move(&mut game),
+            Some(Command::Redo) => redo_move(&mut game),
+            Some(Command::History) => {
+                print_history(&game);
+                continue;
+            }
+            Some(Command::Save(path)) => save_game(&game, &path).map(|()| game.state()),
+            Some(Command::Help) => {
+                print_help();
+                continue;
+            }
+            Some(Command::Quit) => {
+                println!("You left the dungeon.");
+                return;
+            }
+            None => Err("Unknown command. Type `h` for help".to_string()),
+        };
+
+        if let Err(message) = result {
+            println!("{message}.");
+        }
+    }
+
+    let result = game
+        .result()
+        .expect("The game loop only ends when the game is over");
+    match result.state {
+        GameState::Win => println!(
+            "You escaped the dungeon with {} life points!",
+            result.life_points
+        ),
+        GameState::Lose => println!(
+            "You died in room {}, {} monster points were left in the dungeon.",
+            result.rooms_visited, result.monsters_left
+        ),
+        GameState::InGame => unreachable!("A finished game is either won or lost"),
+    }
+    if let Some(potion) = result.last_potion {
+        println!("Your last card was {potion}: its value is added to your score.");
+    }
+    println!("Score: {}", result.score);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_play_command_is_1_based() {
+        assert_eq!(Command::parse("1"), Some(Command::Play(0)));
+        assert_eq!(Command::parse(" 4 \n"), Some(Command::Play(3)));
+        assert_eq!(Command::parse("0"), None);
+    }
+
+    #[test]
+    fn parse_barehanded_play_command() {
+        assert_eq!(Command::parse("b2"), Some(Command::PlayBarehanded(1)));
+        assert_eq!(Command::parse("B 3"), Some(Command::PlayBarehanded(2)));
+        assert_eq!(Command::parse("b"), None);
+    }
+
+    #[test]
+    fn parse_other_commands() {
+        assert_eq!(Command::parse("r"), Some(Command::RunAway));
+        assert_eq!(Command::parse("RUN"), Some(Command::RunAway));
+        assert_eq!(Command::parse("q"), Some(Command::Quit));
+        assert_eq!(Command::parse("help"), Some(Command::Help));
+        assert_eq!(Command::parse("log"), Some(Command::History));
+        assert_eq!(Command::parse("u"), Some(Command::Undo));
+        assert_eq!(Command::parse("redo"), Some(Command::Redo));
+        assert_eq!(
+            Command::parse("save run.json"),
+            Some(Command::Save("run.json".to_string()))
+        );
+        assert_eq!(Command::parse("attack"), None);
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_command_line_options() {
+        assert_eq!(Options::parse(args(&[])), Ok(Options::default()));
+        assert_eq!(
+            Options::parse(args(&["--seed", "42", "--aces-low", "--ranked"])),
+            Ok(Options {
+                seed: Some(42),
+                aces_low: true,
+                ranked: true,
+                load: None,
+                solve: false,
+                max_positions: None,
+                bot: None
+            })
+        );
+        assert!(Options::parse(args(&["--seed"])).is_err());
+        assert!(Options::parse(args(&["--seed", "x"])).is_err());
+        assert_eq!(
+            Options::parse(args(&["--load", "run.json"])).map(|options| options.load),
+            Ok(Some("run.json".to_string()))
+        );
+        assert!(Options::parse(args(&["--load"])).is_err());
+        assert_eq!(
+            Options::parse(args(&["--solve"])).map(|options| options.solve),
+            Ok(true)
+        );
+        assert_eq!(
+            Options::parse(args(&["--bot", "greedy"])).map(|options| options.bot),
+            Ok(Some(Bot::Greedy))
+        );
+        assert!(Options::parse(args(&["--bot", "cheater"])).is_err());
+        assert!(Options::parse(args(&["--load", "run.json", "--seed", "42"])).is_err());
+        assert!(Options::parse(args(&["--aces-low", "--load", "run.json"])).is_err());
+        assert!(Options::parse(args(&["--load", "run.json", "--ranked"])).is_ok());
+        assert_eq!(
+            Options::parse(args(&["--solve", "--max-positions", "1000"]))
+                .map(|options| options.max_positions),
+            Ok(Some(1000))
+        );
+        assert!(Options::parse(args(&["--solve", "--max-positions", "x"])).is_err());
+        assert!(Options::parse(args(&["--max-positions", "1000"])).is_err());
+        assert!(Options::parse(args(&["--fast"])).is_err());
+    }
+
+    #[test]
+    fn parse_simulate_options() {
+        assert_eq!(
+            SimulateOptions::parse(args(&[])),
+            Ok(SimulateOptions::default())
+        );
+        assert_eq!(
+            SimulateOptions::parse(args(&[
+                "--games",
+                "50",
+                "--seed",
+                "100",
+                "--bot",
+                "random",
+                "--aces-low",
+                "--csv"
+            ])),
+            Ok(SimulateOptions {
+                games: 50,
+                first_seed: 100,
+                bot: Bot::Random,
+                aces_low: true,
+                csv: true,
+                threads: None
+            })
+        );
+        assert_eq!(
+            SimulateOptions::parse(args(&["--games", "50", "--seed", "100"]))
+                .map(|options| options.seeds()),
+            Ok(100..150)
+        );
+        assert!(SimulateOptions::parse(args(&["--games", "-1"])).is_err());
+        assert_eq!(
+            SimulateOptions::parse(args(&["--threads", "4"])).map(|options| options.threads),
+            Ok(NonZeroUsize::new(4))
+        );
+        assert!(SimulateOptions::parse(args(&["--threads", "0"])).is_err());
+        assert!(SimulateOptions::parse(args(&["--bot"])).is_err());
+        assert!(SimulateOptions::parse(args(&["--ranked"])).is_err());
+    }
 }
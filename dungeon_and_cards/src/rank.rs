@@ -1,20 +1,24 @@
 //! A module for playing card rank's functionality.
 //!
 //! Provides `Rank` type and operations for standard poker card
-//! with support operation with `u8` type
+//! with support operation with `u8` type, and `RankMode` to value
+//! Aces low (1) or high (14).
 
 use core::fmt;
 use std::{
     cmp::Ordering,
     ops::{Add, AddAssign, Sub, SubAssign},
+    str::FromStr,
 };
 use strum_macros::EnumIter;
 
+use crate::card::{CardIndexError, ParseCardError};
+
 /// Enum representing the thirteen standard playing card ranks.
 ///
 /// # Examples
 /// ```
-/// use your_crate::Rank;
+/// use dungeon_and_cards::Rank;
 ///
 /// let rank = Rank::Ace;
 /// assert_eq!(rank.to_string(), "A");
@@ -69,6 +73,49 @@ impl fmt::Display for Rank {
     }
 }
 
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    /// Parses a rank, ignoring case, from its symbol (`A`, `2` to `10`, `J`,
+    /// `Q`, `K`) or its name (`Queen`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        match text.to_lowercase().as_str() {
+            "a" | "ace" => Ok(Rank::Ace),
+            "2" | "two" => Ok(Rank::Two),
+            "3" | "three" => Ok(Rank::Three),
+            "4" | "four" => Ok(Rank::Four),
+            "5" | "five" => Ok(Rank::Five),
+            "6" | "six" => Ok(Rank::Six),
+            "7" | "seven" => Ok(Rank::Seven),
+            "8" | "eight" => Ok(Rank::Eight),
+            "9" | "nine" => Ok(Rank::Nine),
+            "10" | "ten" => Ok(Rank::Ten),
+            "j" | "jack" => Ok(Rank::Jack),
+            "q" | "queen" => Ok(Rank::Queen),
+            "k" | "king" => Ok(Rank::King),
+            _ => Err(ParseCardError::InvalidRank(text.to_string())),
+        }
+    }
+}
+
+/// A rank is serialized as its symbol, e.g. `"Q"` or `"10"`, and
+/// deserialized from any text `FromStr` accepts.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rank {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rank {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl From<Rank> for u8 {
     fn from(value: Rank) -> Self {
         match value {
@@ -89,12 +136,75 @@ impl From<Rank> for u8 {
     }
 }
 
+impl TryFrom<u8> for Rank {
+    type Error = CardIndexError;
+
+    /// Converts back the number given by `u8::from(rank)`, from 1 (Ace) to
+    /// 13 (King).
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Rank::Ace),
+            2 => Ok(Rank::Two),
+            3 => Ok(Rank::Three),
+            4 => Ok(Rank::Four),
+            5 => Ok(Rank::Five),
+            6 => Ok(Rank::Six),
+            7 => Ok(Rank::Seven),
+            8 => Ok(Rank::Eight),
+            9 => Ok(Rank::Nine),
+            10 => Ok(Rank::Ten),
+            11 => Ok(Rank::Jack),
+            12 => Ok(Rank::Queen),
+            13 => Ok(Rank::King),
+            _ => Err(CardIndexError::InvalidRank(value)),
+        }
+    }
+}
+
+/// How Aces are valued: below the Two or above the King.
+///
+/// The `u8` conversions and the `Ord` implementation of `Rank` are the
+/// fixed, ace-low order of a new deck: game rules value and compare ranks
+/// through a `RankMode` instead.
+///
+/// # Examples
+/// ```
+/// use std::cmp::Ordering;
+/// use dungeon_and_cards::{Rank, RankMode};
+///
+/// assert_eq!(RankMode::AceLow.value(Rank::Ace), 1);
+/// assert_eq!(RankMode::AceHigh.value(Rank::Ace), 14);
+/// assert_eq!(RankMode::AceHigh.compare(Rank::Ace, Rank::King), Ordering::Greater);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RankMode {
+    /// Ace is worth 1, the lowest rank.
+    AceLow,
+    /// Ace is worth 14, the highest rank.
+    AceHigh,
+}
+
+impl RankMode {
+    /// Returns the numeric value of `rank` in this mode.
+    pub fn value(self, rank: Rank) -> u8 {
+        match (self, rank) {
+            (RankMode::AceHigh, Rank::Ace) => 14,
+            _ => u8::from(rank),
+        }
+    }
+
+    /// Compares two ranks according to this mode.
+    pub fn compare(self, rank: Rank, other: Rank) -> Ordering {
+        self.value(rank).cmp(&self.value(other))
+    }
+}
+
 // Overload `+` (addition)
 impl Add<u8> for Rank {
     type Output = u8;
 
     fn add(self, rhs: u8) -> u8 {
-        u8::from(self) + u8::from(rhs)
+        u8::from(self) + rhs
     }
 }
 
@@ -119,7 +229,7 @@ impl Sub<u8> for Rank {
     type Output = u8;
 
     fn sub(self, rhs: u8) -> u8 {
-        u8::from(self).saturating_sub(u8::from(rhs)) // Prevents underflow
+        u8::from(self).saturating_sub(rhs) // Prevents underflow
     }
 }
 
@@ -139,6 +249,8 @@ impl SubAssign<Rank> for u8 {
     }
 }
 
+/// The fixed order of a new deck, Ace first whatever the `RankMode`:
+/// compare ranks with `RankMode::compare` in games.
 impl Ord for Rank {
     fn cmp(&self, other: &Self) -> Ordering {
         u8::from(*self).cmp(&u8::from(*other))
@@ -196,6 +308,30 @@ mod tests {
         assert_eq!(Rank::King as u8, 13u8);
     }
 
+    #[test]
+    fn rank_numbers_convert_back() {
+        use strum::IntoEnumIterator;
+
+        for rank in Rank::iter() {
+            assert_eq!(Rank::try_from(u8::from(rank)), Ok(rank));
+        }
+        assert_eq!(Rank::try_from(0), Err(CardIndexError::InvalidRank(0)));
+        assert_eq!(Rank::try_from(14), Err(CardIndexError::InvalidRank(14)));
+    }
+
+    #[test]
+    fn parse_a_rank() {
+        assert_eq!("A".parse(), Ok(Rank::Ace));
+        assert_eq!("10".parse(), Ok(Rank::Ten));
+        assert_eq!("q".parse(), Ok(Rank::Queen));
+        assert_eq!(" Seven ".parse(), Ok(Rank::Seven));
+        assert_eq!("KING".parse(), Ok(Rank::King));
+        assert_eq!(
+            "11".parse::<Rank>(),
+            Err(ParseCardError::InvalidRank("11".to_string()))
+        );
+    }
+
     #[test]
     fn sum_a_card_with_a_u8() {
         let rank = Rank::Queen;
@@ -227,6 +363,32 @@ mod tests {
         assert!(12 == Rank::Queen);
     }
 
+    #[test]
+    fn ace_value_depends_on_rank_mode() {
+        assert_eq!(RankMode::AceLow.value(Rank::Ace), 1);
+        assert_eq!(RankMode::AceHigh.value(Rank::Ace), 14);
+        assert_eq!(RankMode::AceLow.value(Rank::King), 13);
+        assert_eq!(RankMode::AceHigh.value(Rank::King), 13);
+    }
+
+    #[test]
+    fn ace_high_ordering() {
+        assert_eq!(
+            RankMode::AceHigh.compare(Rank::Ace, Rank::King),
+            Ordering::Greater
+        );
+        assert_eq!(
+            RankMode::AceLow.compare(Rank::Ace, Rank::Two),
+            Ordering::Less
+        );
+        assert_eq!(
+            RankMode::AceHigh.compare(Rank::Five, Rank::Five),
+            Ordering::Equal
+        );
+        // `Ord` stays the order of a new deck.
+        assert!(Rank::Ace < Rank::King);
+    }
+
     #[test]
     fn test_edge_cases() {
         assert!(Rank::Ace == 1);
@@ -0,0 +1,406 @@
+//! Rules of a Scoundrel game.
+//!
+//! `ScoundrelRules::default()` is the standard game; the builder returned by
+//! `ScoundrelRules::builder()` tweaks it into variants: a longer game with
+//! more life points, bigger rooms, more potions...
+
+use core::fmt;
+
+use crate::{
+    card::Card,
+    card_set::CardSet,
+    rank::{Rank, RankMode},
+    suit::Suit,
+};
+
+/// Maximum life points a player can have
+pub const MAX_LIFE_POINTS: u8 = 20;
+
+/// Total number of cards in a room.
+pub const ROOM_SIZE: usize = 4;
+
+/// Number of cards of a room carried over to the next one.
+pub const CARRIED_OVER: usize = 1;
+
+/// Number of health potions that can heal in a single room.
+pub const POTIONS_PER_ROOM: usize = 1;
+
+/// Number of rooms the character can flee in a row.
+pub const RUN_AWAY_LIMIT: usize = 1;
+
+/// Banned cards that are removed from the deck at game start
+const BANNED_CARDS: &[(Suit, Rank)] = &[
+    (Suit::Diamonds, Rank::Ace),
+    (Suit::Diamonds, Rank::Jack),
+    (Suit::Diamonds, Rank::Queen),
+    (Suit::Diamonds, Rank::King),
+    (Suit::Hearts, Rank::Ace),
+    (Suit::Hearts, Rank::Jack),
+    (Suit::Hearts, Rank::Queen),
+    (Suit::Hearts, Rank::King),
+];
+
+/// Invalid rules rejected by `ScoundrelRulesBuilder::build`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RulesError {
+    /// The character must start with some life points.
+    NoStartingLife,
+    /// The character can't start with more than the maximum life points.
+    StartingLifeAboveMax {
+        /// Life points at the start of the game.
+        starting_life: u8,
+        /// Maximum life points.
+        max_life: u8,
+    },
+    /// A room must hold at least one card.
+    EmptyRoom,
+    /// A room can't hold more cards than the deck.
+    RoomLargerThanDeck {
+        /// Number of cards in a room.
+        room_size: usize,
+        /// Number of cards in the deck once the banned cards are removed.
+        deck_size: usize,
+    },
+    /// At least one card of a room has to be played before the next one.
+    TooManyCarriedOver {
+        /// Number of cards carried over to the next room.
+        carried_over: usize,
+        /// Number of cards in a room.
+        room_size: usize,
+    },
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulesError::NoStartingLife => write!(f, "the character must start with life points"),
+            RulesError::StartingLifeAboveMax {
+                starting_life,
+                max_life,
+            } => write!(
+                f,
+                "the starting life ({starting_life}) is above the maximum ({max_life})"
+            ),
+            RulesError::EmptyRoom => write!(f, "a room must hold at least one card"),
+            RulesError::RoomLargerThanDeck {
+                room_size,
+                deck_size,
+            } => write!(
+                f,
+                "rooms of {room_size} cards don't fit in a deck of {deck_size} cards"
+            ),
+            RulesError::TooManyCarriedOver {
+                carried_over,
+                room_size,
+            } => write!(
+                f,
+                "can't carry {carried_over} cards over from rooms of {room_size} cards"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+/// Rules of a Scoundrel game.
+///
+/// # Examples
+/// ```
+/// use dungeon_and_cards::{Scoundrel, ScoundrelRules};
+///
+/// let rules = ScoundrelRules::builder()
+///     .starting_life(30)
+///     .max_life(30)
+///     .potions_per_room(2)
+///     .build()
+///     .unwrap();
+/// let game = Scoundrel::with_rules(rules);
+/// assert_eq!(game.life_points(), 30);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoundrelRules {
+    /// Life points at the start of the game.
+    pub(crate) starting_life: u8,
+    /// Potions can't heal over this limit.
+    pub(crate) max_life: u8,
+    /// Number of cards in a room.
+    pub(crate) room_size: usize,
+    /// Number of cards left in a room when the next one is entered.
+    pub(crate) carried_over: usize,
+    /// Cards removed from the deck at game start.
+    pub(crate) banned_cards: CardSet,
+    /// Number of potions that can heal in a single room.
+    pub(crate) potions_per_room: usize,
+    /// Number of rooms the character can flee in a row, 0 to never flee.
+    pub(crate) run_away_limit: usize,
+    /// How Aces are valued in combat.
+    pub(crate) rank_mode: RankMode,
+}
+
+impl Default for ScoundrelRules {
+    /// The standard rules: 20 life points, rooms of 4 cards with 1 card
+    /// carried over, no red face cards or Aces, 1 potion per room, no
+    /// fleeing two rooms in a row and Aces high.
+    fn default() -> Self {
+        Self {
+            starting_life: MAX_LIFE_POINTS,
+            max_life: MAX_LIFE_POINTS,
+            room_size: ROOM_SIZE,
+            carried_over: CARRIED_OVER,
+            banned_cards: BANNED_CARDS
+                .iter()
+                .map(|&(suit, rank)| Card::new(suit, rank))
+                .collect(),
+            potions_per_room: POTIONS_PER_ROOM,
+            run_away_limit: RUN_AWAY_LIMIT,
+            rank_mode: RankMode::AceHigh,
+        }
+    }
+}
+
+impl ScoundrelRules {
+    /// Creates a builder starting from the standard rules.
+    pub fn builder() -> ScoundrelRulesBuilder {
+        ScoundrelRulesBuilder::new()
+    }
+
+    /// Returns the life points at the start of the game.
+    pub fn starting_life(&self) -> u8 {
+        self.starting_life
+    }
+
+    /// Returns the maximum life points.
+    pub fn max_life(&self) -> u8 {
+        self.max_life
+    }
+
+    /// Returns the number of cards in a room.
+    pub fn room_size(&self) -> usize {
+        self.room_size
+    }
+
+    /// Returns the number of cards carried over to the next room.
+    pub fn carried_over(&self) -> usize {
+        self.carried_over
+    }
+
+    /// Returns the cards removed from the deck.
+    pub fn banned_cards(&self) -> CardSet {
+        self.banned_cards
+    }
+
+    /// Returns the number of potions that can heal in a single room.
+    pub fn potions_per_room(&self) -> usize {
+        self.potions_per_room
+    }
+
+    /// Returns the number of rooms the character can flee in a row.
+    pub fn run_away_limit(&self) -> usize {
+        self.run_away_limit
+    }
+
+    /// Returns how Aces are valued in combat.
+    pub fn rank_mode(&self) -> RankMode {
+        self.rank_mode
+    }
+}
+
+/// Builder for configuring and constructing `ScoundrelRules`.
+///
+/// Every setting not given keeps its standard value.
+#[derive(Debug, Clone)]
+pub struct ScoundrelRulesBuilder {
+    rules: ScoundrelRules,
+}
+
+impl Default for ScoundrelRulesBuilder {
+    /// Same as `ScoundrelRulesBuilder::new`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScoundrelRulesBuilder {
+    /// Creates a new builder with the standard rules.
+    pub fn new() -> Self {
+        Self {
+            rules: ScoundrelRules::default(),
+        }
+    }
+
+    /// Sets the life points at the start of the game.
+    pub fn starting_life(mut self, life_points: u8) -> Self {
+        self.rules.starting_life = life_points;
+        self
+    }
+
+    /// Sets the maximum life points potions can heal up to.
+    pub fn max_life(mut self, life_points: u8) -> Self {
+        self.rules.max_life = life_points;
+        self
+    }
+
+    /// Sets the number of cards in a room.
+    pub fn room_size(mut self, cards: usize) -> Self {
+        self.rules.room_size = cards;
+        self
+    }
+
+    /// Sets the number of cards left in a room when the next one is entered.
+    pub fn carried_over(mut self, cards: usize) -> Self {
+        self.rules.carried_over = cards;
+        self
+    }
+
+    /// Replaces the cards removed from the deck.
+    ///
+    /// # Arguments
+    /// * `cards` - An iterator of cards to ban, empty for a full deck
+    pub fn banned_cards(mut self, cards: impl IntoIterator<Item = Card>) -> Self {
+        self.rules.banned_cards = cards.into_iter().collect();
+        self
+    }
+
+    /// Sets the number of potions that can heal in a single room.
+    pub fn potions_per_room(mut self, potions: usize) -> Self {
+        self.rules.potions_per_room = potions;
+        self
+    }
+
+    /// Sets the number of rooms the character can flee in a row,
+    /// 0 to never flee.
+    pub fn run_away_limit(mut self, rooms: usize) -> Self {
+        self.rules.run_away_limit = rooms;
+        self
+    }
+
+    /// Sets how Aces are valued in combat.
+    pub fn rank_mode(mut self, rank_mode: RankMode) -> Self {
+        self.rules.rank_mode = rank_mode;
+        self
+    }
+
+    /// Constructs the rules, checking that a game can be played with them.
+    ///
+    /// # Errors
+    /// - `RulesError::NoStartingLife` if the starting life is 0
+    /// - `RulesError::StartingLifeAboveMax` if it is above the maximum
+    /// - `RulesError::EmptyRoom` if the room size is 0
+    /// - `RulesError::RoomLargerThanDeck` if the room size is above the number
+    ///   of cards left once the banned ones are removed
+    /// - `RulesError::TooManyCarriedOver` if no card of a room has to be played
+    pub fn build(self) -> Result<ScoundrelRules, RulesError> {
+        let rules = self.rules;
+        if rules.starting_life == 0 {
+            return Err(RulesError::NoStartingLife);
+        }
+        if rules.starting_life > rules.max_life {
+            return Err(RulesError::StartingLifeAboveMax {
+                starting_life: rules.starting_life,
+                max_life: rules.max_life,
+            });
+        }
+        if rules.room_size == 0 {
+            return Err(RulesError::EmptyRoom);
+        }
+        let deck_size = CardSet::full().difference(rules.banned_cards).len();
+        if rules.room_size > deck_size {
+            return Err(RulesError::RoomLargerThanDeck {
+                room_size: rules.room_size,
+                deck_size,
+            });
+        }
+        if rules.carried_over >= rules.room_size {
+            return Err(RulesError::TooManyCarriedOver {
+                carried_over: rules.carried_over,
+                room_size: rules.room_size,
+            });
+        }
+        Ok(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_are_the_standard_game() {
+        let rules = ScoundrelRules::default();
+
+        assert_eq!(rules.starting_life(), 20);
+        assert_eq!(rules.max_life(), 20);
+        assert_eq!(rules.room_size(), 4);
+        assert_eq!(rules.carried_over(), 1);
+        assert_eq!(rules.banned_cards().len(), 8);
+        assert_eq!(rules.potions_per_room(), 1);
+        assert_eq!(rules.run_away_limit(), 1);
+        assert_eq!(rules.rank_mode(), RankMode::AceHigh);
+        assert_eq!(ScoundrelRules::builder().build(), Ok(rules));
+    }
+
+    #[test]
+    fn builder_overrides_the_given_settings() {
+        let rules = ScoundrelRules::builder()
+            .starting_life(10)
+            .max_life(30)
+            .room_size(5)
+            .carried_over(2)
+            .banned_cards([])
+            .potions_per_room(2)
+            .run_away_limit(0)
+            .rank_mode(RankMode::AceLow)
+            .build()
+            .expect("Valid rules");
+
+        assert_eq!(rules.starting_life(), 10);
+        assert_eq!(rules.max_life(), 30);
+        assert_eq!(rules.room_size(), 5);
+        assert_eq!(rules.carried_over(), 2);
+        assert!(rules.banned_cards().is_empty());
+        assert_eq!(rules.potions_per_room(), 2);
+        assert_eq!(rules.run_away_limit(), 0);
+        assert_eq!(rules.rank_mode(), RankMode::AceLow);
+    }
+
+    #[test]
+    fn builder_rejects_unplayable_rules() {
+        assert_eq!(
+            ScoundrelRules::builder().starting_life(0).build(),
+            Err(RulesError::NoStartingLife)
+        );
+        assert_eq!(
+            ScoundrelRules::builder().starting_life(25).build(),
+            Err(RulesError::StartingLifeAboveMax {
+                starting_life: 25,
+                max_life: 20
+            })
+        );
+        assert_eq!(
+            ScoundrelRules::builder().room_size(0).build(),
+            Err(RulesError::EmptyRoom)
+        );
+        assert_eq!(
+            ScoundrelRules::builder().room_size(usize::MAX).build(),
+            Err(RulesError::RoomLargerThanDeck {
+                room_size: usize::MAX,
+                deck_size: 44
+            })
+        );
+        assert_eq!(
+            ScoundrelRules::builder()
+                .room_size(52)
+                .banned_cards([])
+                .build()
+                .map(|rules| rules.room_size()),
+            Ok(52)
+        );
+        assert_eq!(
+            ScoundrelRules::builder().carried_over(4).build(),
+            Err(RulesError::TooManyCarriedOver {
+                carried_over: 4,
+                room_size: 4
+            })
+        );
+    }
+}
@@ -0,0 +1,768 @@
+//! Versioned save files of Scoundrel games, behind the `save` feature.
+//!
+//! A save is a JSON document `{"version": N, "game": {...}}`. The layout of
+//! each version is frozen in its own `SaveVN` types: when `Scoundrel`
+//! changes, a new version is added and the older ones are converted on load,
+//! so old saves keep loading.
+//!
+//! Cards are written with their rank and suit letter, e.g. `QC` for the
+//! Queen of Clubs or `10H` for the Ten of Hearts.
+
+use core::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card::Card,
+    card_set::CardSet,
+    deck::Deck,
+    journal::Event,
+    rank::RankMode,
+    rules::{
+        ScoundrelRules, CARRIED_OVER, MAX_LIFE_POINTS, POTIONS_PER_ROOM, ROOM_SIZE, RUN_AWAY_LIMIT,
+    },
+    scoundrel::{CombatMode, GameState, RoomRecord, RoomStatus, Scoundrel, Weapon},
+};
+
+/// Version of the save files written by this version of the game.
+pub const SAVE_VERSION: u32 = 2;
+
+/// Errors returned while loading a save.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveError {
+    /// The save is not valid JSON, doesn't match the layout of its version,
+    /// holds invalid rules or an impossible game.
+    Malformed(String),
+    /// The save was written by a newer version of the game.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveError::Malformed(reason) => write!(f, "malformed save: {reason}"),
+            SaveError::UnsupportedVersion(version) => write!(
+                f,
+                "save version {version} is not supported, the latest is {SAVE_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(error: serde_json::Error) -> Self {
+        SaveError::Malformed(error.to_string())
+    }
+}
+
+impl Scoundrel {
+    /// Saves the game as JSON, in the latest save version.
+    ///
+    /// The undo history is not saved: a loaded game starts without it.
+    pub fn to_save(&self) -> String {
+        let file = SaveFile {
+            version: SAVE_VERSION,
+            game: SaveV2::from(self),
+        };
+        serde_json::to_string(&file).expect("A save always serializes")
+    }
+
+    /// Loads a game saved by `to_save`, with any supported save version.
+    ///
+    /// # Errors
+    /// - `SaveError::UnsupportedVersion` if the save is newer than the game
+    /// - `SaveError::Malformed` if the save can't be read, or describes a
+    ///   game that can't be: no seed, a card in two places or banned, a room
+    ///   bigger than the rules allow
+    pub fn from_save(save: &str) -> Result<Self, SaveError> {
+        let header: SaveHeader = serde_json::from_str(save)?;
+        let save = match header.version {
+            1 => SaveV2::from(serde_json::from_str::<SaveFile<SaveV1>>(save)?.game),
+            2 => serde_json::from_str::<SaveFile<SaveV2>>(save)?.game,
+            version => return Err(SaveError::UnsupportedVersion(version)),
+        };
+        save.try_into()
+    }
+}
+
+/// The version of a save, read before its content.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+/// A save file: its version and the game, in the layout of that version.
+#[derive(Serialize, Deserialize)]
+struct SaveFile<T> {
+    version: u32,
+    game: T,
+}
+
+/// Save version 1: the standard rules but the Aces, the whole game but
+/// its undo history.
+#[derive(Deserialize)]
+struct SaveV1 {
+    /// Seed of the deal.
+    seed: Option<u64>,
+    /// Aces are worth 1 instead of 14.
+    aces_low: bool,
+    /// Cards left in the deck, from the top.
+    deck: Vec<Card>,
+    /// Cards banned from the deck.
+    banned_cards: Vec<Card>,
+    life_points: u8,
+    weapon: Option<WeaponV1>,
+    /// Rooms visited, the latest is the current one. Gives the room
+    /// counter and whether the character just ran away.
+    rooms: Vec<RoomV1>,
+    /// Cards left in the current room.
+    room: Vec<Card>,
+    state: GameStateV1,
+    potions_used: usize,
+    wasted_potions: Vec<Card>,
+    journal: Vec<EventV1>,
+    undo_enabled: bool,
+}
+
+/// Save version 2: the rules and the whole game but its undo history.
+///
+/// Adds the rules to version 1, and the journal records every card carried
+/// over to a room.
+#[derive(Serialize, Deserialize)]
+struct SaveV2 {
+    /// Seed of the deal.
+    seed: Option<u64>,
+    rules: RulesV2,
+    /// Cards left in the deck, from the top.
+    deck: Vec<Card>,
+    life_points: u8,
+    weapon: Option<WeaponV1>,
+    /// Rooms visited, the latest is the current one. Gives the room
+    /// counter and how many rooms the character just fled.
+    rooms: Vec<RoomV1>,
+    /// Cards left in the current room.
+    room: Vec<Card>,
+    state: GameStateV1,
+    potions_used: usize,
+    wasted_potions: Vec<Card>,
+    journal: Vec<EventV2>,
+    undo_enabled: bool,
+}
+
+impl From<SaveV1> for SaveV2 {
+    fn from(save: SaveV1) -> Self {
+        Self {
+            seed: save.seed,
+            rules: RulesV2 {
+                starting_life: MAX_LIFE_POINTS,
+                max_life: MAX_LIFE_POINTS,
+                room_size: ROOM_SIZE,
+                carried_over: CARRIED_OVER,
+                banned_cards: save.banned_cards,
+                potions_per_room: POTIONS_PER_ROOM,
+                run_away_limit: RUN_AWAY_LIMIT,
+                aces_low: save.aces_low,
+            },
+            deck: save.deck,
+            life_points: save.life_points,
+            weapon: save.weapon,
+            rooms: save.rooms,
+            room: save.room,
+            state: save.state,
+            potions_used: save.potions_used,
+            wasted_potions: save.wasted_potions,
+            journal: save.journal.into_iter().map(EventV2::from).collect(),
+            undo_enabled: save.undo_enabled,
+        }
+    }
+}
+
+impl From<&Scoundrel> for SaveV2 {
+    fn from(game: &Scoundrel) -> Self {
+        Self {
+            seed: game.deck.seed(),
+            rules: RulesV2::from(&game.rules),
+            deck: game.deck.iter().copied().collect(),
+            life_points: game.life_points,
+            weapon: game.weapon_equipped.as_ref().map(WeaponV1::from),
+            rooms: game.rooms.iter().map(RoomV1::from).collect(),
+            room: game.room.clone(),
+            state: game.state.into(),
+            potions_used: game.potions_used,
+            wasted_potions: game.wasted_potions.clone(),
+            journal: game.journal.iter().map(EventV2::from).collect(),
+            undo_enabled: game.undo_enabled,
+        }
+    }
+}
+
+impl TryFrom<SaveV2> for Scoundrel {
+    type Error = SaveError;
+
+    fn try_from(save: SaveV2) -> Result<Self, SaveError> {
+        let rules = ScoundrelRules::try_from(save.rules)?;
+        let banned_cards = rules.banned_cards;
+        let seed = save
+            .seed
+            .ok_or_else(|| SaveError::Malformed("the seed of the deal is missing".to_string()))?;
+        if save.room.len() > rules.room_size {
+            return Err(SaveError::Malformed(format!(
+                "the room holds {} cards, more than the {} of the rules",
+                save.room.len(),
+                rules.room_size
+            )));
+        }
+        // Each card of the dungeon is in one place at most, and not banned.
+        let weapon_cards = save
+            .weapon
+            .iter()
+            .flat_map(|weapon| std::iter::once(&weapon.weapon).chain(&weapon.defeated_monsters));
+        let mut cards = CardSet::new();
+        for card in save.deck.iter().chain(&save.room).chain(weapon_cards) {
+            if banned_cards.contains(*card) {
+                return Err(SaveError::Malformed(format!("{card} is banned")));
+            }
+            if !cards.insert(*card) {
+                return Err(SaveError::Malformed(format!("{card} is in the game twice")));
+            }
+        }
+
+        Ok(Self {
+            deck: Deck::from_parts(save.deck, banned_cards, Some(seed)),
+            life_points: save.life_points,
+            weapon_equipped: save.weapon.map(Weapon::from),
+            rooms: Arc::new(save.rooms.into_iter().map(RoomRecord::from).collect()),
+            room: save.room,
+            state: save.state.into(),
+            potions_used: save.potions_used,
+            wasted_potions: save.wasted_potions,
+            rules,
+            journal: Arc::new(save.journal.into_iter().map(Event::from).collect()),
+            undo_enabled: save.undo_enabled,
+            undo_stack: Arc::default(),
+            redo_stack: Arc::default(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RulesV2 {
+    starting_life: u8,
+    max_life: u8,
+    room_size: usize,
+    carried_over: usize,
+    banned_cards: Vec<Card>,
+    potions_per_room: usize,
+    run_away_limit: usize,
+    /// Aces are worth 1 instead of 14.
+    aces_low: bool,
+}
+
+impl From<&ScoundrelRules> for RulesV2 {
+    fn from(rules: &ScoundrelRules) -> Self {
+        Self {
+            starting_life: rules.starting_life,
+            max_life: rules.max_life,
+            room_size: rules.room_size,
+            carried_over: rules.carried_over,
+            banned_cards: rules.banned_cards.iter().collect(),
+            potions_per_room: rules.potions_per_room,
+            run_away_limit: rules.run_away_limit,
+            aces_low: rules.rank_mode == RankMode::AceLow,
+        }
+    }
+}
+
+impl TryFrom<RulesV2> for ScoundrelRules {
+    type Error = SaveError;
+
+    fn try_from(rules: RulesV2) -> Result<Self, SaveError> {
+        let rank_mode = if rules.aces_low {
+            RankMode::AceLow
+        } else {
+            RankMode::AceHigh
+        };
+        ScoundrelRules::builder()
+            .starting_life(rules.starting_life)
+            .max_life(rules.max_life)
+            .room_size(rules.room_size)
+            .carried_over(rules.carried_over)
+            .banned_cards(rules.banned_cards)
+            .potions_per_room(rules.potions_per_room)
+            .run_away_limit(rules.run_away_limit)
+            .rank_mode(rank_mode)
+            .build()
+            .map_err(|error| SaveError::Malformed(error.to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct WeaponV1 {
+    weapon: Card,
+    defeated_monsters: Vec<Card>,
+}
+
+impl From<&Weapon> for WeaponV1 {
+    fn from(weapon: &Weapon) -> Self {
+        Self {
+            weapon: weapon.weapon,
+            defeated_monsters: weapon.defeated_monsters.clone(),
+        }
+    }
+}
+
+impl From<WeaponV1> for Weapon {
+    fn from(weapon: WeaponV1) -> Self {
+        Self {
+            weapon: weapon.weapon,
+            defeated_monsters: weapon.defeated_monsters,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RoomV1 {
+    cards: Vec<Card>,
+    played: Vec<Card>,
+    status: RoomStatusV1,
+}
+
+impl From<&RoomRecord> for RoomV1 {
+    fn from(room: &RoomRecord) -> Self {
+        Self {
+            cards: room.cards.clone(),
+            played: room.played.clone(),
+            status: room.status.into(),
+        }
+    }
+}
+
+impl From<RoomV1> for RoomRecord {
+    fn from(room: RoomV1) -> Self {
+        Self {
+            cards: room.cards,
+            played: room.played,
+            status: room.status.into(),
+        }
+    }
+}
+
+/// Converts back and forth between an enum of the game and its frozen copy.
+macro_rules! frozen_enum {
+    ($frozen:ident, $current:ident { $($variant:ident),+ $(,)? }) => {
+        #[derive(Serialize, Deserialize)]
+        enum $frozen {
+            $($variant),+
+        }
+
+        impl From<$current> for $frozen {
+            fn from(value: $current) -> Self {
+                match value {
+                    $($current::$variant => $frozen::$variant),+
+                }
+            }
+        }
+
+        impl From<$frozen> for $current {
+            fn from(value: $frozen) -> Self {
+                match value {
+                    $($frozen::$variant => $current::$variant),+
+                }
+            }
+        }
+    };
+}
+
+frozen_enum!(GameStateV1, GameState { InGame, Win, Lose });
+frozen_enum!(
+    RoomStatusV1,
+    RoomStatus {
+        Entered,
+        Fled,
+        Cleared
+    }
+);
+frozen_enum!(CombatModeV1, CombatMode { Weapon, Barehanded });
+
+#[derive(Deserialize)]
+enum EventV1 {
+    RoomEntered {
+        carried: Option<Card>,
+        drawn: Vec<Card>,
+    },
+    CardPlayed {
+        card: Card,
+        combat: Option<CombatModeV1>,
+        damage: u8,
+        heal: u8,
+    },
+    WeaponEquipped {
+        weapon: Card,
+        discarded: Option<WeaponV1>,
+    },
+    PotionWasted {
+        potion: Card,
+    },
+    MonsterSlain {
+        monster: Card,
+        weapon: Option<Card>,
+    },
+    Fled {
+        cards: Vec<Card>,
+    },
+    GameOver {
+        state: GameStateV1,
+    },
+}
+
+/// Same as `EventV1`, but every card carried over to a room is recorded.
+#[derive(Serialize, Deserialize)]
+enum EventV2 {
+    RoomEntered {
+        carried: Vec<Card>,
+        drawn: Vec<Card>,
+    },
+    CardPlayed {
+        card: Card,
+        combat: Option<CombatModeV1>,
+        damage: u8,
+        heal: u8,
+    },
+    WeaponEquipped {
+        weapon: Card,
+        discarded: Option<WeaponV1>,
+    },
+    PotionWasted {
+        potion: Card,
+    },
+    MonsterSlain {
+        monster: Card,
+        weapon: Option<Card>,
+    },
+    Fled {
+        cards: Vec<Card>,
+    },
+    GameOver {
+        state: GameStateV1,
+    },
+}
+
+impl From<EventV1> for EventV2 {
+    fn from(event: EventV1) -> Self {
+        match event {
+            EventV1::RoomEntered { carried, drawn } => EventV2::RoomEntered {
+                carried: carried.into_iter().collect(),
+                drawn,
+            },
+            EventV1::CardPlayed {
+                card,
+                combat,
+                damage,
+                heal,
+            } => EventV2::CardPlayed {
+                card,
+                combat,
+                damage,
+                heal,
+            },
+            EventV1::WeaponEquipped { weapon, discarded } => {
+                EventV2::WeaponEquipped { weapon, discarded }
+            }
+            EventV1::PotionWasted { potion } => EventV2::PotionWasted { potion },
+            EventV1::MonsterSlain { monster, weapon } => EventV2::MonsterSlain { monster, weapon },
+            EventV1::Fled { cards } => EventV2::Fled { cards },
+            EventV1::GameOver { state } => EventV2::GameOver { state },
+        }
+    }
+}
+
+impl From<&Event> for EventV2 {
+    fn from(event: &Event) -> Self {
+        match event.clone() {
+            Event::RoomEntered { carried, drawn } => EventV2::RoomEntered { carried, drawn },
+            Event::CardPlayed {
+                card,
+                combat,
+                damage,
+                heal,
+            } => EventV2::CardPlayed {
+                card,
+                combat: combat.map(CombatModeV1::from),
+                damage,
+                heal,
+            },
+            Event::WeaponEquipped { weapon, discarded } => EventV2::WeaponEquipped {
+                weapon,
+                discarded: discarded.as_ref().map(WeaponV1::from),
+            },
+            Event::PotionWasted { potion } => EventV2::PotionWasted { potion },
+            Event::MonsterSlain { monster, weapon } => EventV2::MonsterSlain { monster, weapon },
+            Event::Fled { cards } => EventV2::Fled { cards },
+            Event::GameOver { state } => EventV2::GameOver {
+                state: state.into(),
+            },
+        }
+    }
+}
+
+impl From<EventV2> for Event {
+    fn from(event: EventV2) -> Self {
+        match event {
+            EventV2::RoomEntered { carried, drawn } => Event::RoomEntered { carried, drawn },
+            EventV2::CardPlayed {
+                card,
+                combat,
+                damage,
+                heal,
+            } => Event::CardPlayed {
+                card,
+                combat: combat.map(CombatMode::from),
+                damage,
+                heal,
+            },
+            EventV2::WeaponEquipped { weapon, discarded } => Event::WeaponEquipped {
+                weapon,
+                discarded: discarded.map(Weapon::from),
+            },
+            EventV2::PotionWasted { potion } => Event::PotionWasted { potion },
+            EventV2::MonsterSlain { monster, weapon } => Event::MonsterSlain { monster, weapon },
+            EventV2::Fled { cards } => Event::Fled { cards },
+            EventV2::GameOver { state } => Event::GameOver {
+                state: state.into(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rank::Rank, scoundrel::Action, suit::Suit};
+
+    /// Plays a seeded game with the first legal action, running away
+    /// whenever possible, for `actions` actions at most.
+    fn played_game(seed: u64, actions: usize) -> Scoundrel {
+        let mut game = Scoundrel::from_seed(seed);
+        for _ in 0..actions {
+            if game.state() != GameState::InGame {
+                break;
+            }
+            let legal = game.legal_actions();
+            let action = legal
+                .iter()
+                .find(|action| **action == Action::RunAway)
+                .unwrap_or(&legal[0]);
+            game.apply(*action).expect("The action is legal");
+        }
+        game
+    }
+
+    #[test]
+    fn cards_are_saved_in_short_notation() {
+        let game = played_game(7, 3);
+        let save: serde_json::Value = serde_json::from_str(&game.to_save()).expect("JSON");
+
+        let card = game.room()[0];
+        let code = format!("{}{}", card.rank(), card.suit().letter());
+        assert_eq!(save["game"]["room"][0], serde_json::json!(code));
+    }
+
+    #[test]
+    fn loaded_game_is_identical() {
+        for actions in [0, 1, 5, 12, 200] {
+            let game = played_game(7, actions);
+            let loaded = Scoundrel::from_save(&game.to_save()).expect("A valid save");
+
+            assert_eq!(loaded.to_save(), game.to_save());
+            assert_eq!(loaded.deck, game.deck);
+            assert_eq!(loaded.state(), game.state());
+            assert_eq!(loaded.room(), game.room());
+            assert_eq!(loaded.life_points(), game.life_points());
+            assert_eq!(loaded.weapon_equipped(), game.weapon_equipped());
+            assert_eq!(loaded.has_run_away(), game.has_run_away());
+            assert_eq!(loaded.rooms_visited(), game.rooms_visited());
+            assert_eq!(loaded.journal(), game.journal());
+        }
+    }
+
+    #[test]
+    fn loaded_game_plays_on_like_the_original() {
+        let mut game = played_game(11, 6);
+        let mut loaded = Scoundrel::from_save(&game.to_save()).expect("A valid save");
+
+        while game.state() == GameState::InGame {
+            let action = game.legal_actions()[0];
+            game.apply(action).expect("The action is legal");
+            loaded.apply(action).expect("The same action is legal");
+        }
+        assert_eq!(loaded.result(), game.result());
+        assert_eq!(loaded.journal(), game.journal());
+    }
+
+    #[test]
+    fn version_1_saves_keep_loading() {
+        let save = r#"{"version":1,"game":{"seed":3,"aces_low":false,
+            "deck":["2S","QC"],"banned_cards":["AD","JD","QD","KD","AH","JH","QH","KH"],
+            "life_points":14,"weapon":{"weapon":"5D","defeated_monsters":["4C"]},
+            "rooms":[{"cards":["7H","3S","5D","4C"],"played":["5D","4C"],"status":"Cleared"},
+                     {"cards":["7H","9S","2H","10D"],"played":[],"status":"Entered"}],
+            "room":["7H","9S","2H","10D"],"state":"InGame","potions_used":0,
+            "wasted_potions":[],"journal":[{"RoomEntered":{"carried":null,"drawn":["2S"]}},
+            {"RoomEntered":{"carried":"7H","drawn":["9S"]}}],"undo_enabled":false}}"#;
+
+        let game = Scoundrel::from_save(save).expect("A version 1 save");
+
+        assert_eq!(game.seed(), 3);
+        assert_eq!(game.cards_left(), 2);
+        assert_eq!(game.life_points(), 14);
+        assert_eq!(game.rooms_visited(), 2);
+        assert!(!game.has_run_away());
+        assert!(!game.undo_enabled());
+        assert_eq!(game.rules(), &ScoundrelRules::default());
+        assert_eq!(
+            game.journal()[1],
+            Event::RoomEntered {
+                carried: vec![Card::new(Suit::Hearts, Rank::Seven)],
+                drawn: vec![Card::new(Suit::Spades, Rank::Nine)]
+            }
+        );
+        assert_eq!(
+            game.weapon_equipped()
+                .expect("A weapon is equipped")
+                .defeated_monsters(),
+            vec![Card::new(Suit::Clubs, Rank::Four)]
+        );
+    }
+
+    /// Saves a game after editing its JSON.
+    fn edited_save(game: &Scoundrel, edit: impl FnOnce(&mut serde_json::Value)) -> String {
+        let mut save: serde_json::Value =
+            serde_json::from_str(&game.to_save()).expect("A valid save");
+        edit(&mut save["game"]);
+        save.to_string()
+    }
+
+    #[test]
+    fn save_without_a_seed_is_rejected() {
+        let save = edited_save(&played_game(3, 4), |game| {
+            game["seed"] = serde_json::Value::Null;
+        });
+
+        assert!(matches!(
+            Scoundrel::from_save(&save),
+            Err(SaveError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn save_with_a_card_twice_is_rejected() {
+        let game = played_game(3, 1);
+        let card = game.room()[0];
+
+        // In the room and in the deck.
+        let save = edited_save(&game, |game| {
+            game["deck"][0] = serde_json::json!(card);
+        });
+        assert_eq!(
+            Scoundrel::from_save(&save).err(),
+            Some(SaveError::Malformed(format!("{card} is in the game twice")))
+        );
+
+        // Equipped and in the room.
+        let save = edited_save(&game, |game| {
+            game["weapon"] = serde_json::json!({
+                "weapon": card,
+                "defeated_monsters": [],
+            });
+        });
+        assert_eq!(
+            Scoundrel::from_save(&save).err(),
+            Some(SaveError::Malformed(format!("{card} is in the game twice")))
+        );
+    }
+
+    #[test]
+    fn save_with_a_banned_card_is_rejected() {
+        let banned = Card::new(Suit::Hearts, Rank::King);
+        let save = edited_save(&played_game(3, 1), |game| {
+            game["deck"][0] = serde_json::json!("KH");
+        });
+
+        assert_eq!(
+            Scoundrel::from_save(&save).err(),
+            Some(SaveError::Malformed(format!("{banned} is banned")))
+        );
+    }
+
+    #[test]
+    fn save_with_an_oversized_room_is_rejected() {
+        let game = played_game(3, 1);
+        let save = edited_save(&game, |game| {
+            let card = game["deck"][0].clone();
+            game["deck"]
+                .as_array_mut()
+                .expect("A list of cards")
+                .remove(0);
+            game["room"]
+                .as_array_mut()
+                .expect("A list of cards")
+                .push(card);
+        });
+
+        assert!(matches!(
+            Scoundrel::from_save(&save),
+            Err(SaveError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn rules_are_saved() {
+        let rules = ScoundrelRules::builder()
+            .starting_life(15)
+            .room_size(5)
+            .carried_over(2)
+            .banned_cards([])
+            .rank_mode(RankMode::AceLow)
+            .build()
+            .expect("Valid rules");
+        let mut game = Scoundrel::from_seed_with_rules(4, rules);
+        game.enter_room().expect("First room");
+
+        let loaded = Scoundrel::from_save(&game.to_save()).expect("A valid save");
+        assert_eq!(loaded.rules(), game.rules());
+        assert_eq!(loaded.cards_left(), 47);
+    }
+
+    #[test]
+    fn newer_saves_are_rejected() {
+        assert_eq!(
+            Scoundrel::from_save(r#"{"version":3,"game":{}}"#).err(),
+            Some(SaveError::UnsupportedVersion(3))
+        );
+    }
+
+    #[test]
+    fn invalid_saves_are_rejected() {
+        assert!(matches!(
+            Scoundrel::from_save("not json"),
+            Err(SaveError::Malformed(_))
+        ));
+        let save = Scoundrel::from_seed(3)
+            .to_save()
+            .replace("\"QC\"", "\"ZC\"");
+        assert!(matches!(
+            Scoundrel::from_save(&save),
+            Err(SaveError::Malformed(_))
+        ));
+        let save = Scoundrel::from_seed(3)
+            .to_save()
+            .replace("\"room_size\":4", "\"room_size\":0");
+        assert!(matches!(
+            Scoundrel::from_save(&save),
+            Err(SaveError::Malformed(_))
+        ));
+    }
+}
@@ -4,20 +4,19 @@
 //! where players navigate rooms, battle monsters, and manage resources.
 //!
 
-use crate::{card::Card, deck::Deck, rank::Rank, suit::Suit};
+use core::fmt;
+use std::sync::Arc;
 
-/// Maximum life points a player can have
-pub const MAX_LIFE_POINTS: u8 = 20;
+use rand::Rng;
 
-/// Total rooms in the game
-pub const TOTAL_ROOMS: usize = 12;
-
-/// Total number of cards in a room.
-pub const ROOM_SIZE: usize = 4;
+use crate::{
+    card::Card, card_set::CardSet, deck::Deck, journal::Event, rank::RankMode,
+    rules::ScoundrelRules, suit::Suit,
+};
 
 /// Represents the current state of the game.
-#[derive(PartialEq, Eq, Debug)]
-enum GameState {
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum GameState {
     /// The game is still in progress.
     InGame,
     /// The player has won the game.
@@ -26,14 +25,126 @@ enum GameState {
     Lose,
 }
 
+/// Illegal actions reported by `Scoundrel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoundrelError {
+    /// The character ran away from as many rooms in a row as the rules
+    /// allow, so can't flee this one.
+    AlreadyRanAway,
+    /// Running away is only possible before playing any card of the room.
+    RoomAlreadyStarted,
+    /// There is no room to run away from: the next room has to be entered first.
+    NotInRoom,
+    /// The current room isn't finished: more cards than the rules carry over
+    /// are still in it.
+    RoomNotFinished {
+        /// Number of cards still in the room.
+        cards_left: usize,
+    },
+    /// The card is not in the current room.
+    CardNotInRoom(Card),
+    /// The monster can't be fought with a weapon: no weapon is equipped,
+    /// or the weapon already slayed a weaker monster.
+    CannotUseWeapon(Card),
+    /// The action doesn't suit the card: monsters are fought with
+    /// `Action::Fight`, weapons and potions are played with `Action::PlayCard`.
+    WrongActionForCard(Card),
+    /// The game is over, no more actions are allowed.
+    GameOver,
+    /// Undo and redo are disabled for this game.
+    UndoDisabled,
+    /// No action left to undo.
+    NothingToUndo,
+    /// No undone action left to redo.
+    NothingToRedo,
+}
+
+impl fmt::Display for ScoundrelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScoundrelError::AlreadyRanAway => {
+                write!(f, "Scoundrel can't run away from one more room in a row")
+            }
+            ScoundrelError::RoomAlreadyStarted => {
+                write!(f, "Scoundrel can only run away from a new room")
+            }
+            ScoundrelError::NotInRoom => write!(f, "Scoundrel is not in a room"),
+            ScoundrelError::RoomNotFinished { cards_left } => write!(
+                f,
+                "The room is not finished yet, {cards_left} cards are left in it"
+            ),
+            ScoundrelError::CardNotInRoom(card) => write!(f, "{card} is not in the room"),
+            ScoundrelError::CannotUseWeapon(card) => {
+                write!(f, "The weapon can't be used against {card}")
+            }
+            ScoundrelError::WrongActionForCard(card) if is_monster(card) => {
+                write!(f, "{card} is a monster, it has to be fought")
+            }
+            ScoundrelError::WrongActionForCard(card) => {
+                write!(f, "{card} is not a monster, it can't be fought")
+            }
+            ScoundrelError::GameOver => write!(f, "The game is over"),
+            ScoundrelError::UndoDisabled => write!(f, "Undo is disabled for this game"),
+            ScoundrelError::NothingToUndo => write!(f, "There is nothing to undo"),
+            ScoundrelError::NothingToRedo => write!(f, "There is nothing to redo"),
+        }
+    }
+}
+
+impl std::error::Error for ScoundrelError {}
+
+/// How the character fights a monster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CombatMode {
+    /// Fight with the equipped weapon, adding the monster to its stack.
+    Weapon,
+    /// Fight barehanded, taking the full damage but sparing the weapon.
+    Barehanded,
+}
+
+/// An action the player can take, see `Scoundrel::legal_actions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Play a weapon or a potion of the room.
+    PlayCard(Card),
+    /// Fight a monster of the room with the given combat mode.
+    Fight(Card, CombatMode),
+    /// Run away from the current room.
+    RunAway,
+    /// Enter the next room.
+    EnterRoom,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::PlayCard(card) => write!(f, "play {card}"),
+            Action::Fight(card, CombatMode::Weapon) => write!(f, "fight {card} with the weapon"),
+            Action::Fight(card, CombatMode::Barehanded) => write!(f, "fight {card} barehanded"),
+            Action::RunAway => write!(f, "run away"),
+            Action::EnterRoom => write!(f, "enter the next room"),
+        }
+    }
+}
+
+/// Damage the character would take from a monster, for each combat mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CombatPreview {
+    /// Damage taken fighting barehanded.
+    pub barehanded: u8,
+    /// Damage taken fighting with the equipped weapon,
+    /// `None` if the weapon can't be used against the monster.
+    pub weapon: Option<u8>,
+}
+
 /// The character weapon.
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Weapon {
+pub struct Weapon {
     /// This card represent the weapon equipped.
-    weapon: Card,
+    pub(crate) weapon: Card,
     /// This card represent the stack of monsters slayed with the weapon.
     /// The weapon can't slay a monster greater or equal to the latest slayed.
-    defeated_monsters: Vec<Card>,
+    pub(crate) defeated_monsters: Vec<Card>,
 }
 
 impl Weapon {
@@ -46,8 +157,14 @@ impl Weapon {
         }
     }
 
-    fn defeated_monsters(&self) -> Vec<Card> {
-        self.defeated_monsters.clone()
+    /// Returns the card used as weapon.
+    pub fn weapon(&self) -> Card {
+        self.weapon
+    }
+
+    /// Returns the stack of monsters slayed with the weapon, oldest first.
+    pub fn defeated_monsters(&self) -> &[Card] {
+        &self.defeated_monsters
     }
 
     fn add_defeated_monster(&mut self, monster: Card) {
@@ -55,133 +172,442 @@ impl Weapon {
     }
 }
 
+/// Final result of a game, to compare runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameResult {
+    /// Whether the game was won or lost.
+    pub state: GameState,
+    /// The final score, see `Scoundrel::score`.
+    pub score: i32,
+    /// Life points left at the end of the game.
+    pub life_points: u8,
+    /// Number of rooms visited.
+    pub rooms_visited: usize,
+    /// Total value of the monsters left in the dungeon.
+    pub monsters_left: u32,
+    /// The potion that ended a won game, adding its value to the score.
+    pub last_potion: Option<Card>,
+}
+
+/// What happened to a room of the dungeon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomStatus {
+    /// The character is in the room.
+    Entered,
+    /// The character ran away: the cards went back under the deck.
+    Fled,
+    /// All the cards but one have been played.
+    Cleared,
+}
+
+/// A room of the dungeon, as visited by the character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomRecord {
+    /// The cards in the room when it was entered, carried over card first.
+    pub(crate) cards: Vec<Card>,
+    /// The cards played in the room, in order.
+    pub(crate) played: Vec<Card>,
+    /// What happened to the room.
+    pub(crate) status: RoomStatus,
+}
+
+impl RoomRecord {
+    fn new(cards: Vec<Card>) -> Self {
+        Self {
+            played: Vec::with_capacity(cards.len()),
+            cards,
+            status: RoomStatus::Entered,
+        }
+    }
+
+    /// Returns the cards in the room when it was entered.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Returns the cards played in the room, in order.
+    pub fn played(&self) -> &[Card] {
+        &self.played
+    }
+
+    /// Returns what happened to the room.
+    pub fn status(&self) -> RoomStatus {
+        self.status
+    }
+}
+
 /// The main game struct representing the player's state
 ///
 /// # Fields
 /// - `deck`: The game deck with banned cards removed
-/// - `life_points`: Player's health (max 20)
+/// - `life_points`: Player's health (max 20 in the standard rules)
 /// - `weapon_equipped`: Currently equipped weapon card
-/// - `room_visited`: Number of rooms entered
+/// - `rooms`: History of the rooms entered, fled and cleared
+///
+/// Clones are cheap whatever the length of the game: they share its history
+/// (rooms, journal, undo and redo stacks) until one of them adds to it.
 ///
 /// # Examples
 /// ```
-/// use scoundrel::Scoundrel;
+/// use dungeon_and_cards::Scoundrel;
 ///
-/// let mut game = Scoundrel::new();
-/// assert_eq!(game.life_points, 20);
+/// let game = Scoundrel::new();
+/// assert_eq!(game.life_points(), 20);
 /// ```
-struct Scoundrel {
+#[derive(Clone, Debug)]
+pub struct Scoundrel {
     /// The deck of cards used in the game. Some cards are banned at initialization.
-    deck: Deck,
-    /// Current life points of the character. Maximum is 20.
-    life_points: u8,
+    pub(crate) deck: Deck,
+    /// Current life points of the character, up to the rules maximum.
+    pub(crate) life_points: u8,
     /// Currently equipped weapon, if any.
-    weapon_equipped: Option<Weapon>,
-    /// Number of rooms the character has visited.
-    room_visited: usize,
+    pub(crate) weapon_equipped: Option<Weapon>,
+    /// Rooms the character has visited, the latest is the current one.
+    pub(crate) rooms: Arc<Vec<RoomRecord>>,
     /// Current room visited.
-    room: Vec<Card>,
-    /// Keeps track if ran away from latest room.
-    has_run_away: bool
+    pub(crate) room: Vec<Card>,
+    /// Whether the game is still in progress, won or lost.
+    pub(crate) state: GameState,
+    /// Number of potions used to heal in the current room.
+    pub(crate) potions_used: usize,
+    /// Potions consumed without effect, because the room limit was reached.
+    pub(crate) wasted_potions: Vec<Card>,
+    /// Rules of the game, the standard ones unless built `with_rules`.
+    pub(crate) rules: ScoundrelRules,
+    /// Every mutation of the game, in order.
+    pub(crate) journal: Arc<Vec<Event>>,
+    /// Whether actions can be undone. Disabled for ranked play.
+    pub(crate) undo_enabled: bool,
+    /// Snapshots of the game before each action, the latest last.
+    /// Snapshots have empty undo and redo stacks.
+    pub(crate) undo_stack: Arc<Vec<Scoundrel>>,
+    /// Snapshots of the game before each undo, the latest last.
+    pub(crate) redo_stack: Arc<Vec<Scoundrel>>,
 }
 
-impl Scoundrel {
-    /// Banned cards that are removed from the deck at game start
-    const BANNED_CARDS: &'static [(Suit, Rank)] = &[
-        (Suit::Diamonds, Rank::Ace),
-        (Suit::Diamonds, Rank::Jack),
-        (Suit::Diamonds, Rank::Queen),
-        (Suit::Diamonds, Rank::King),
-        (Suit::Hearts, Rank::Ace),
-        (Suit::Hearts, Rank::Jack),
-        (Suit::Hearts, Rank::Queen),
-        (Suit::Hearts, Rank::King),
-    ];
+impl Default for Scoundrel {
+    /// Same as `Scoundrel::new`: the standard rules and a random deal.
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
+impl Scoundrel {
     /// Creates a new Scoundrel game instance
     ///
-    /// Initializes with:
-    /// - 44-card deck (standard 52 minus banned cards)
-    /// - `MAX_LIFE_POINTS` life points
+    /// Initializes with the standard rules:
+    /// - 44-card deck (standard 52 minus red face cards and Aces) shuffled
+    ///   with a random seed
+    /// - 20 life points
     /// - No equipped weapon
     /// - Starting room (0)
+    /// - Aces high: a black Ace is the strongest monster (14)
     pub fn new() -> Self {
-        let banned_cards: Vec<_> = Self::BANNED_CARDS
-            .iter()
-            .map(|&(suit, rank)| Card::new(suit, rank))
-            .collect();
+        Self::with_rules(ScoundrelRules::default())
+    }
+
+    /// Creates a new Scoundrel game instance valuing Aces with `rank_mode`.
+    pub fn with_rank_mode(rank_mode: RankMode) -> Self {
+        Self::from_seed_with_rank_mode(rand::random(), rank_mode)
+    }
+
+    /// Creates a new Scoundrel game instance played with `rules`.
+    ///
+    /// # Examples
+    /// ```
+    /// use dungeon_and_cards::{Scoundrel, ScoundrelRules};
+    ///
+    /// let rules = ScoundrelRules::builder().room_size(5).build().unwrap();
+    /// let mut game = Scoundrel::with_rules(rules);
+    /// game.enter_room().unwrap();
+    /// assert_eq!(game.room().len(), 5);
+    /// ```
+    pub fn with_rules(rules: ScoundrelRules) -> Self {
+        Self::from_seed_with_rules(rand::random(), rules)
+    }
+
+    /// Creates a new Scoundrel game instance whose deal is fully determined
+    /// by `seed`: the same seed always gives the same game.
+    ///
+    /// # Examples
+    /// ```
+    /// use dungeon_and_cards::Scoundrel;
+    ///
+    /// let mut game = Scoundrel::from_seed(42);
+    /// let mut same = Scoundrel::from_seed(42);
+    /// game.enter_room().unwrap();
+    /// same.enter_room().unwrap();
+    /// assert_eq!(game.room(), same.room());
+    /// ```
+    pub fn from_seed(seed: u64) -> Self {
+        Self::from_seed_with_rules(seed, ScoundrelRules::default())
+    }
+
+    /// Creates a new seeded Scoundrel game instance valuing Aces with `rank_mode`.
+    pub fn from_seed_with_rank_mode(seed: u64, rank_mode: RankMode) -> Self {
+        let rules = ScoundrelRules {
+            rank_mode,
+            ..ScoundrelRules::default()
+        };
+        Self::from_seed_with_rules(seed, rules)
+    }
+
+    /// Creates a new seeded Scoundrel game instance played with `rules`.
+    pub fn from_seed_with_rules(seed: u64, rules: ScoundrelRules) -> Self {
+        let deck = Deck::builder()
+            .ban_cards(rules.banned_cards)
+            .seed(seed)
+            .build();
 
         Self {
-            deck: Deck::builder().ban_cards(banned_cards).build(),
-            life_points: MAX_LIFE_POINTS,
+            deck,
+            life_points: rules.starting_life,
             weapon_equipped: None,
-            room_visited: 0,
-            room: Vec::with_capacity(ROOM_SIZE),
-            has_run_away: false,
+            rooms: Arc::default(),
+            room: Vec::new(),
+            state: GameState::InGame,
+            potions_used: 0,
+            wasted_potions: Vec::new(),
+            rules,
+            journal: Arc::default(),
+            undo_enabled: true,
+            undo_stack: Arc::default(),
+            redo_stack: Arc::default(),
         }
     }
 
-    /// Enters a new room, drawing 4 cards
+    /// Disables undo and redo for the whole game, e.g. for ranked play.
+    ///
+    /// It also saves the cost of a snapshot per action.
+    pub fn without_undo(mut self) -> Self {
+        self.undo_enabled = false;
+        self.undo_stack = Arc::default();
+        self.redo_stack = Arc::default();
+        self
+    }
+
+    /// Enters a new room, drawing 4 cards in the standard rules
+    ///
+    /// A room can be entered at the beginning of the game, after running away
+    /// or when a single card (`ScoundrelRules::carried_over`) is left in the
+    /// previous room: that card is carried over and 3 cards are drawn to
+    /// complete the room.
+    ///
+    /// When the deck runs short, the room holds the cards left: it is the
+    /// last room and all its cards have to be played.
     ///
     /// # Returns
-    /// - `Some(Vec<Card>)` with 4 drawn cards if rooms remain
-    /// - `None` if all `TOTAL_ROOMS` rooms have been visited
+    /// - `GameState::InGame` with the new room in `room()` if cards remain
+    /// - `GameState::Win` if the deck is empty and the last room resolved
+    ///
+    /// # Errors
+    /// - `ScoundrelError::GameOver` if the game has already ended
+    /// - `ScoundrelError::RoomNotFinished` if more cards than carried over are
+    ///   left in the room, or any card is left in the last room
     ///
     /// # Examples
     /// ```
+    /// use dungeon_and_cards::Scoundrel;
+    ///
     /// let mut game = Scoundrel::new();
-    /// let cards = game.enter_room().expect("First room should succeed");
-    /// assert_eq!(cards.len(), 4);
+    /// let state = game.enter_room().expect("First room should succeed");
+    /// assert_eq!(game.room().len(), 4);
     /// ```
-    pub fn enter_room(&mut self) -> GameState {
-        // In case the deck is over, end the game
-        if self.room_visited >= TOTAL_ROOMS {
-            return GameState::Win;
+    pub fn enter_room(&mut self) -> Result<GameState, ScoundrelError> {
+        self.check_enter_room()?;
+        self.record_undo();
+        Ok(self.enter_next_room())
+    }
+
+    /// Enters the next room, once `check_enter_room` passed.
+    fn enter_next_room(&mut self) -> GameState {
+        let cards_left = self.room.len();
+
+        // The previous room is over, unless the character fled it.
+        if let Some(previous) = Arc::make_mut(&mut self.rooms)
+            .last_mut()
+            .filter(|room| room.status == RoomStatus::Entered)
+        {
+            previous.status = RoomStatus::Cleared;
+        }
+
+        // In case the deck is over and the last room resolved, end the game
+        if self.deck.is_empty() {
+            self.state = GameState::Win;
+            Arc::make_mut(&mut self.journal).push(Event::GameOver { state: self.state });
+            return self.state;
         }
 
-        self.room_visited += 1;
+        self.potions_used = 0;
+        // In case new game or ran away from a room, hand is empty.
+        // In case exited a room, hand has the cards carried over.
+        let carried = self.room.clone();
+        let drawn = self.deck.draw_up_to(self.rules.room_size - cards_left);
+        self.room.extend_from_slice(&drawn);
+        Arc::make_mut(&mut self.rooms).push(RoomRecord::new(self.room.clone()));
+        Arc::make_mut(&mut self.journal).push(Event::RoomEntered { carried, drawn });
+
+        self.state
+    }
+
+    /// Runs away from the current room, putting its cards at the bottom of the deck.
+    ///
+    /// The character can't flee two rooms in a row (`ScoundrelRules::run_away_limit`):
+    /// a room has to be cleared before running away again.
+    ///
+    /// # Errors
+    /// - `ScoundrelError::GameOver` if the game has already ended
+    /// - `ScoundrelError::AlreadyRanAway` if the character fled as many rooms
+    ///   in a row as allowed
+    /// - `ScoundrelError::NotInRoom` if no room has been entered
+    /// - `ScoundrelError::RoomAlreadyStarted` if a card of the room has been played
+    pub fn run_away(&mut self) -> Result<(), ScoundrelError> {
+        self.check_run_away()?;
+        self.record_undo();
+
+        let cards = self.room.clone();
+        self.deck.bottom(&mut self.room);
+        if let Some(current) = Arc::make_mut(&mut self.rooms).last_mut() {
+            current.status = RoomStatus::Fled;
+        }
+        Arc::make_mut(&mut self.journal).push(Event::Fled { cards });
+        Ok(())
+    }
+
+    /// Checks that the next room can be entered, see `enter_room`.
+    fn check_enter_room(&self) -> Result<(), ScoundrelError> {
+        self.ensure_in_game()?;
+
+        let cards_left = self.room.len();
+        // No card is carried over from the last room.
+        if cards_left > self.rules.carried_over || (cards_left > 0 && self.deck.is_empty()) {
+            return Err(ScoundrelError::RoomNotFinished { cards_left });
+        }
+        Ok(())
+    }
+
+    /// Checks that the character can run away, see `run_away`.
+    fn check_run_away(&self) -> Result<(), ScoundrelError> {
+        self.ensure_in_game()?;
+
+        if self.consecutive_run_aways() >= self.rules.run_away_limit {
+            return Err(ScoundrelError::AlreadyRanAway);
+        }
+
+        // It can ran away only from a new room
         match self.room.len() {
-            0 => {
-                // In case new game or ran away from a room, hand is empty.
-                self.room.append(&mut self.deck.draw(4));
+            0 => Err(ScoundrelError::NotInRoom),
+            cards if cards == self.rules.room_size => Ok(()),
+            _ => Err(ScoundrelError::RoomAlreadyStarted),
+        }
+    }
+
+    /// Checks that the card can be played with the combat mode,
+    /// returning its position in the room. See `play_card_with`.
+    fn check_play_card(&self, card: &Card, mode: CombatMode) -> Result<usize, ScoundrelError> {
+        self.ensure_in_game()?;
+
+        let position = self
+            .room
+            .iter()
+            .position(|c| c == card)
+            .ok_or(ScoundrelError::CardNotInRoom(*card))?;
+
+        if is_monster(card) && mode == CombatMode::Weapon && !self.can_use_weapon(card) {
+            return Err(ScoundrelError::CannotUseWeapon(*card));
+        }
+        Ok(position)
+    }
+
+    /// Lists every action allowed in the current state, empty once the game is over.
+    ///
+    /// Monsters are listed once per usable combat mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use dungeon_and_cards::{Action, Scoundrel};
+    ///
+    /// let mut game = Scoundrel::new();
+    /// assert_eq!(game.legal_actions(), vec![Action::EnterRoom]);
+    ///
+    /// game.apply(Action::EnterRoom).unwrap();
+    /// assert!(game.legal_actions().contains(&Action::RunAway));
+    /// ```
+    pub fn legal_actions(&self) -> Vec<Action> {
+        let mut actions = Vec::with_capacity(2 * self.room.len() + 1);
+
+        for card in &self.room {
+            if !is_monster(card) {
+                actions.push(Action::PlayCard(*card));
+                continue;
             }
-            1 => {
-                // In case exited a room, hand has 1 card
-                self.room.append(&mut self.deck.draw(3));
+            if self.check_play_card(card, CombatMode::Weapon).is_ok() {
+                actions.push(Action::Fight(*card, CombatMode::Weapon));
             }
-            _ => {
-                todo!() // TODO: should throw an error, or the room is empty (beginning or run) or has 1 card (standard case)
+            if self.check_play_card(card, CombatMode::Barehanded).is_ok() {
+                actions.push(Action::Fight(*card, CombatMode::Barehanded));
             }
         }
-
-        GameState::InGame
+        if self.check_run_away().is_ok() {
+            actions.push(Action::RunAway);
+        }
+        if self.check_enter_room().is_ok() {
+            actions.push(Action::EnterRoom);
+        }
+        actions
     }
 
-    pub fn run_away(&mut self) -> Result<(), &'static str> {
-        match self.has_run_away {
-            true => Err("Scoundrel can't run away two rooms in a row"),
-            false => {
-                // It can ran away only from a new room
-                if self.room.len() == 4 {
-                    self.deck.bottom(&mut self.room);
-                    return Ok(());
-                }
+    /// Returns `true` if the action is allowed in the current state.
+    pub fn is_legal(&self, action: &Action) -> bool {
+        match action {
+            Action::PlayCard(card) => {
+                !is_monster(card) && self.check_play_card(card, CombatMode::Barehanded).is_ok()
+            }
+            Action::Fight(card, mode) => {
+                is_monster(card) && self.check_play_card(card, *mode).is_ok()
+            }
+            Action::RunAway => self.check_run_away().is_ok(),
+            Action::EnterRoom => self.check_enter_room().is_ok(),
+        }
+    }
 
-                Err("Scoundrel can only run away from a new room (4 cards)")
-            },
+    /// Executes a single action, the entry point for UIs and bots.
+    ///
+    /// # Returns
+    /// Updated `GameState` after the action
+    ///
+    /// # Errors
+    /// The errors of `play_card_with`, `run_away` and `enter_room`, and
+    /// `ScoundrelError::WrongActionForCard` if a monster is played with
+    /// `Action::PlayCard` or another card with `Action::Fight`: it fails
+    /// for every action `is_legal` rejects.
+    pub fn apply(&mut self, action: Action) -> Result<GameState, ScoundrelError> {
+        match action {
+            Action::PlayCard(card) | Action::Fight(card, _)
+                if is_monster(&card) != matches!(action, Action::Fight(..)) =>
+            {
+                self.check_play_card(&card, CombatMode::Barehanded)?;
+                Err(ScoundrelError::WrongActionForCard(card))
+            }
+            Action::PlayCard(card) => self.play_card(&card),
+            Action::Fight(card, mode) => self.play_card_with(&card, mode),
+            Action::RunAway => self.run_away().map(|()| self.state),
+            Action::EnterRoom => self.enter_room(),
         }
     }
 
     fn fight_barehanded(&mut self, monster: &Card) -> GameState {
+        let attack_power = self.value(monster);
+
         // In case the rank is higher than the `self.life_points`
         // the character dies. GAME OVER
-        if monster.rank() >= self.life_points {
+        if attack_power >= self.life_points {
             self.life_points = 0;
             return GameState::Lose;
         }
 
         // In case of barehanded fight, the damage is inflicted directly to the character.
-        self.life_points -= monster.rank();
+        self.life_points -= attack_power;
         GameState::InGame
     }
 
@@ -202,14 +628,6 @@ impl Scoundrel {
     /// A tuple containing:
     /// 1. Updated game state (InGame, Lose, etc.)
     /// 2. Modified weapon (with monster added to its history if used)
-    ///
-    /// # Examples
-    /// ```
-    /// let mut character = Character::new();
-    /// let monster = Card::new(/* ... */);
-    /// let weapon = Weapon::new(/* ... */);
-    /// let (state, updated_weapon) = character.fight_with_weapon(&monster, weapon);
-    /// ```
     fn fight_with_weapon(&mut self, monster: &Card, mut weapon: Weapon) -> (GameState, Weapon) {
         if self.can_slay_with_weapon(monster, &weapon) {
             let attack_power = self.calculate_attack_power(monster, &weapon);
@@ -222,7 +640,7 @@ impl Scoundrel {
             }
 
             self.life_points -= attack_power;
-            return (GameState::InGame, weapon);
+            (GameState::InGame, weapon)
         } else {
             (self.fight_barehanded(monster), weapon)
         }
@@ -234,53 +652,346 @@ impl Scoundrel {
         weapon
             .defeated_monsters
             .last()
-            .is_none_or(|last_monster| last_monster.rank() > monster.rank())
+            .is_none_or(|last_monster| self.value(last_monster) > self.value(monster))
     }
 
     fn calculate_attack_power(&self, monster: &Card, weapon: &Weapon) -> u8 {
-        (monster.rank() - weapon.weapon.rank().into()).max(0) // TODO: maybe fix the into() with an impl
+        // A stronger weapon deals no damage.
+        self.value(monster)
+            .saturating_sub(self.value(&weapon.weapon))
+    }
+
+    /// Returns the value of a card, with Aces valued by the game `RankMode`.
+    fn value(&self, card: &Card) -> u8 {
+        self.rules.rank_mode.value(card.rank())
     }
 
-    fn handle_combat(&mut self, card: &Card) -> GameState {
+    fn handle_combat(&mut self, card: &Card, mode: CombatMode) -> GameState {
         // Explicitly taking ownership of the weapon.
         // It will be re-equipped after the fight.
         let weapon = self.weapon_equipped.take();
 
-        // In case weapon equipped check if it is possible
+        // In case weapon equipped and chosen check if it is possible
         // to use it or if character has to fight barehanded
-        let state = match weapon {
-            Some(weapon) => {
+        match (mode, weapon) {
+            (CombatMode::Weapon, Some(weapon)) => {
                 let (state, weapon_updated) = self.fight_with_weapon(card, weapon);
                 self.weapon_equipped = Some(weapon_updated);
                 state
             }
-            None => self.fight_barehanded(card),
-        };
+            (_, weapon) => {
+                self.weapon_equipped = weapon;
+                self.fight_barehanded(card)
+            }
+        }
+    }
 
-        state
+    /// Returns `true` if the equipped weapon can be used against the monster.
+    pub fn can_use_weapon(&self, monster: &Card) -> bool {
+        self.weapon_equipped
+            .as_ref()
+            .is_some_and(|weapon| self.can_slay_with_weapon(monster, weapon))
     }
 
-    /// Plays a card from hand, modifying game state
+    /// Previews the damage of fighting a monster barehanded or with the weapon,
+    /// without modifying the game.
+    ///
+    /// # Returns
+    /// `None` if the card is not a monster (Spades or Clubs).
+    pub fn combat_preview(&self, monster: &Card) -> Option<CombatPreview> {
+        if !is_monster(monster) {
+            return None;
+        }
+
+        let weapon = self
+            .weapon_equipped
+            .as_ref()
+            .filter(|weapon| self.can_slay_with_weapon(monster, weapon))
+            .map(|weapon| self.calculate_attack_power(monster, weapon));
+
+        Some(CombatPreview {
+            barehanded: self.value(monster),
+            weapon,
+        })
+    }
+
+    /// Plays a card from the current room, modifying game state
+    ///
+    /// The card is removed from the current room. Once three of the four
+    /// cards of the room have been played (in the standard rules), the next
+    /// room is entered and the last card is carried over to it. When the deck
+    /// is empty, all the cards of the last room have to be played to win.
+    ///
+    /// Monsters are fought with the equipped weapon whenever it can be used,
+    /// barehanded otherwise. Use `play_card_with` to choose.
     ///
     /// # Arguments
     /// * `card` - The card to play
     ///
     /// # Returns
-    /// Updated `GameState` after playing the card
+    /// Updated `GameState` after playing the card (and entering the next room)
+    ///
+    /// # Errors
+    /// - `ScoundrelError::GameOver` if the game has already ended
+    /// - `ScoundrelError::CardNotInRoom` if the card is not in the current room
     ///
     /// # Card Effects
-    /// - **Spades/Clubs**: {Deals damage equal to rank} TODO
+    /// - **Spades/Clubs**: Deals damage equal to rank, reduced by the equipped weapon
     /// - **Diamonds**: Equips as weapon
-    /// - **Hearts**: Heals life points equal to rank
-    pub fn play_card(&mut self, card: &Card) -> GameState {
-        match card.suit() {
-            Suit::Spades | Suit::Clubs => return self.handle_combat(card),
-            Suit::Diamonds => self.weapon_equipped = Some(Weapon::new(*card)),
+    /// - **Hearts**: Heals life points equal to rank, only
+    ///   `ScoundrelRules::potions_per_room` potion per room: the others are
+    ///   discarded without effect
+    pub fn play_card(&mut self, card: &Card) -> Result<GameState, ScoundrelError> {
+        let mode = if self.can_use_weapon(card) {
+            CombatMode::Weapon
+        } else {
+            CombatMode::Barehanded
+        };
+        self.play_card_with(card, mode)
+    }
+
+    /// Plays a card from the current room, fighting monsters with the given
+    /// combat mode. The mode is ignored for weapons and potions.
+    ///
+    /// # Errors
+    /// - `ScoundrelError::GameOver` if the game has already ended
+    /// - `ScoundrelError::CardNotInRoom` if the card is not in the current room
+    /// - `ScoundrelError::CannotUseWeapon` if `CombatMode::Weapon` is chosen
+    ///   but the weapon can't be used against the monster
+    pub fn play_card_with(
+        &mut self,
+        card: &Card,
+        mode: CombatMode,
+    ) -> Result<GameState, ScoundrelError> {
+        let position = self.check_play_card(card, mode)?;
+        self.record_undo();
+        self.room.remove(position);
+        if let Some(current) = Arc::make_mut(&mut self.rooms).last_mut() {
+            current.played.push(*card);
+        }
+
+        let life_points = self.life_points;
+        let mut consequence = None;
+        let combat = match card.suit() {
+            Suit::Spades | Suit::Clubs => {
+                self.state = self.handle_combat(card, mode);
+                consequence = Some(match self.state {
+                    GameState::Lose => Event::GameOver { state: self.state },
+                    _ => Event::MonsterSlain {
+                        monster: *card,
+                        weapon: match mode {
+                            CombatMode::Weapon => self.weapon_equipped.as_ref().map(Weapon::weapon),
+                            CombatMode::Barehanded => None,
+                        },
+                    },
+                });
+                Some(mode)
+            }
+            Suit::Diamonds => {
+                let discarded = self.weapon_equipped.replace(Weapon::new(*card));
+                consequence = Some(Event::WeaponEquipped {
+                    weapon: *card,
+                    discarded,
+                });
+                None
+            }
             Suit::Hearts => {
-                self.life_points = (self.life_points + card.rank()).min(20);
+                if !self.drink_potion(card) {
+                    consequence = Some(Event::PotionWasted { potion: *card });
+                }
+                None
             }
+        };
+
+        Arc::make_mut(&mut self.journal).push(Event::CardPlayed {
+            card: *card,
+            combat,
+            damage: life_points.saturating_sub(self.life_points),
+            heal: self.life_points.saturating_sub(life_points),
+        });
+        Arc::make_mut(&mut self.journal).extend(consequence);
+
+        // The room is over: move forward to the next one,
+        // or out of the dungeon.
+        if self.state == GameState::InGame && self.is_room_resolved() {
+            return Ok(self.enter_next_room());
         }
-        GameState::InGame
+        Ok(self.state)
+    }
+
+    /// Returns `true` if actions can be undone in this game.
+    pub fn undo_enabled(&self) -> bool {
+        self.undo_enabled
+    }
+
+    /// Returns `true` if there is an action to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Returns `true` if there is an undone action to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Steps back the latest action, whatever it was: a room entered,
+    /// a card played (even the one that ended the game) or a run away.
+    ///
+    /// The room entered automatically after clearing a room is undone
+    /// with the card that cleared it.
+    ///
+    /// # Errors
+    /// - `ScoundrelError::UndoDisabled` if undo has been disabled
+    /// - `ScoundrelError::NothingToUndo` if no action has been taken
+    pub fn undo(&mut self) -> Result<GameState, ScoundrelError> {
+        if !self.undo_enabled {
+            return Err(ScoundrelError::UndoDisabled);
+        }
+        let previous = Arc::make_mut(&mut self.undo_stack)
+            .pop()
+            .ok_or(ScoundrelError::NothingToUndo)?;
+
+        let current = self.restore(previous);
+        Arc::make_mut(&mut self.redo_stack).push(current);
+        Ok(self.state)
+    }
+
+    /// Takes again the latest undone action. Any new action clears the
+    /// actions to redo.
+    ///
+    /// # Errors
+    /// - `ScoundrelError::UndoDisabled` if undo has been disabled
+    /// - `ScoundrelError::NothingToRedo` if no action has been undone
+    pub fn redo(&mut self) -> Result<GameState, ScoundrelError> {
+        if !self.undo_enabled {
+            return Err(ScoundrelError::UndoDisabled);
+        }
+        let next = Arc::make_mut(&mut self.redo_stack)
+            .pop()
+            .ok_or(ScoundrelError::NothingToRedo)?;
+
+        let current = self.restore(next);
+        Arc::make_mut(&mut self.undo_stack).push(current);
+        Ok(self.state)
+    }
+
+    /// Saves a snapshot of the game before an action, if undo is enabled.
+    fn record_undo(&mut self) {
+        if !self.undo_enabled {
+            return;
+        }
+        let snapshot = self.snapshot();
+        Arc::make_mut(&mut self.undo_stack).push(snapshot);
+        self.redo_stack = Arc::default();
+    }
+
+    /// Clones the game, without its undo and redo stacks.
+    fn snapshot(&mut self) -> Scoundrel {
+        let undo_stack = std::mem::take(&mut self.undo_stack);
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        let snapshot = self.clone();
+        self.undo_stack = undo_stack;
+        self.redo_stack = redo_stack;
+        snapshot
+    }
+
+    /// Restores a snapshot, keeping the undo and redo stacks.
+    /// Returns the replaced game, without its stacks.
+    fn restore(&mut self, snapshot: Scoundrel) -> Scoundrel {
+        let undo_stack = std::mem::take(&mut self.undo_stack);
+        let redo_stack = std::mem::take(&mut self.redo_stack);
+        let current = std::mem::replace(self, snapshot);
+        self.undo_stack = undo_stack;
+        self.redo_stack = redo_stack;
+        current
+    }
+
+    /// Heals the character, unless a potion has already been used in the room:
+    /// in that case the potion is wasted.
+    ///
+    /// # Returns
+    /// `false` if the potion was wasted.
+    fn drink_potion(&mut self, potion: &Card) -> bool {
+        if self.potions_used >= self.rules.potions_per_room {
+            self.wasted_potions.push(*potion);
+            return false;
+        }
+
+        self.potions_used += 1;
+        self.life_points = self
+            .life_points
+            .saturating_add(self.value(potion))
+            .min(self.rules.max_life);
+        true
+    }
+
+    /// Returns `true` if only the cards carried over are left in the room,
+    /// or no card at all in the last room of the dungeon.
+    fn is_room_resolved(&self) -> bool {
+        if self.deck.is_empty() {
+            self.room.is_empty()
+        } else {
+            self.room.len() == self.rules.carried_over
+        }
+    }
+
+    /// Fails with `ScoundrelError::GameOver` once the game has ended.
+    fn ensure_in_game(&self) -> Result<(), ScoundrelError> {
+        match self.state {
+            GameState::InGame => Ok(()),
+            GameState::Win | GameState::Lose => Err(ScoundrelError::GameOver),
+        }
+    }
+
+    /// Returns the current state of the game
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Returns the score of a finished game, `None` while it is in progress.
+    ///
+    /// - **Lose**: life points (0) minus the total value of the monsters
+    ///   left in the dungeon, the current room included.
+    /// - **Win**: remaining life points, plus the value of the last card
+    ///   played if it was a potion.
+    pub fn score(&self) -> Option<i32> {
+        self.result().map(|result| result.score)
+    }
+
+    /// Returns the final result of a finished game, `None` while it is in progress.
+    pub fn result(&self) -> Option<GameResult> {
+        let monsters_left: u32 = self
+            .deck
+            .iter()
+            .chain(self.room.iter())
+            .filter(|card| is_monster(card))
+            .map(|card| u32::from(self.value(card)))
+            .sum();
+        let last_played = self
+            .rooms
+            .iter()
+            .rev()
+            .find_map(|room| room.played.last())
+            .copied();
+
+        let (score, last_potion) = match self.state {
+            GameState::InGame => return None,
+            GameState::Lose => (i32::from(self.life_points) - monsters_left as i32, None),
+            GameState::Win => {
+                let last_potion = last_played.filter(|card| card.suit() == Suit::Hearts);
+                let bonus = last_potion.map_or(0, |potion| self.value(&potion));
+                (i32::from(self.life_points) + i32::from(bonus), last_potion)
+            }
+        };
+
+        Some(GameResult {
+            state: self.state,
+            score,
+            life_points: self.life_points,
+            rooms_visited: self.rooms.len(),
+            monsters_left,
+            last_potion,
+        })
     }
 
     /// Returns current life points
@@ -288,6 +999,21 @@ impl Scoundrel {
         self.life_points
     }
 
+    /// Returns the seed of the deal, to replay the same game with `from_seed`
+    pub fn seed(&self) -> u64 {
+        self.deck.seed().expect("Scoundrel decks are always seeded")
+    }
+
+    /// Returns how Aces are valued in this game
+    pub fn rank_mode(&self) -> RankMode {
+        self.rules.rank_mode
+    }
+
+    /// Returns the rules of this game
+    pub fn rules(&self) -> &ScoundrelRules {
+        &self.rules
+    }
+
     /// Returns currently equipped weapon, if any
     pub fn weapon_equipped(&self) -> Option<&Weapon> {
         self.weapon_equipped.as_ref()
@@ -295,13 +1021,121 @@ impl Scoundrel {
 
     /// Returns number of rooms visited
     pub fn rooms_visited(&self) -> usize {
-        self.room_visited
+        self.rooms.len()
+    }
+
+    /// Estimates the number of rooms left to enter after the current one.
+    ///
+    /// Each room draws the cards missing to fill it, some cards being carried
+    /// over from the previous room. Running away puts the cards back under
+    /// the deck, so the estimate grows accordingly.
+    pub fn rooms_remaining(&self) -> usize {
+        let ScoundrelRules {
+            room_size,
+            carried_over,
+            ..
+        } = self.rules;
+        let mut cards = self.deck.len();
+        // Cards of the current room are carried over to the next one.
+        let mut carried = self.room.len().min(carried_over);
+        let mut rooms = 0;
+
+        while cards > 0 {
+            cards -= (room_size - carried).min(cards);
+            carried = carried_over;
+            rooms += 1;
+        }
+        rooms
+    }
+
+    /// Shuffles the cards of the deck the character hasn't seen yet.
+    ///
+    /// Cards are seen once drawn into a room: the ones of the fled rooms stay
+    /// at the bottom of the deck, in order. Bots use it to sample the deals
+    /// they can't tell apart from this one, on copies of the game only: the
+    /// journal doesn't record it, so the game no longer replays from its seed.
+    pub(crate) fn shuffle_unseen_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let seen: CardSet = self.rooms.iter().flat_map(|room| &room.cards).collect();
+        let seen = self
+            .deck
+            .iter()
+            .filter(|card| seen.contains(**card))
+            .count();
+        self.deck.shuffle_top_with(self.deck.len() - seen, rng);
+    }
+
+    /// Returns every mutation of the game so far, oldest first.
+    ///
+    /// With the seed and the rules, it rebuilds the game with `Replay`.
+    pub fn journal(&self) -> &[Event] {
+        &self.journal
+    }
+
+    /// Returns the rooms visited so far, oldest first.
+    /// The latest one is the current room while the game is in progress.
+    pub fn room_history(&self) -> &[RoomRecord] {
+        &self.rooms
+    }
+
+    /// Returns `true` if the character fled the latest room left,
+    /// so it can't run away from the current one in the standard rules.
+    pub fn has_run_away(&self) -> bool {
+        self.consecutive_run_aways() > 0
+    }
+
+    /// Returns the number of rooms the character fled in a row,
+    /// up to the latest room left.
+    pub fn consecutive_run_aways(&self) -> usize {
+        self.rooms
+            .iter()
+            .rev()
+            .skip_while(|room| room.status == RoomStatus::Entered)
+            .take_while(|room| room.status == RoomStatus::Fled)
+            .count()
+    }
+
+    /// Returns the cards of the current room
+    pub fn room(&self) -> &[Card] {
+        &self.room
     }
+
+    /// Returns the number of potions used to heal in the current room
+    pub fn potions_used_in_room(&self) -> usize {
+        self.potions_used
+    }
+
+    /// Returns the potions discarded without effect, oldest first
+    pub fn wasted_potions(&self) -> &[Card] {
+        &self.wasted_potions
+    }
+
+    /// Returns the number of cards left in the dungeon deck
+    pub fn cards_left(&self) -> usize {
+        self.deck.len()
+    }
+}
+
+/// Spades and Clubs are the monsters of the dungeon.
+fn is_monster(card: &Card) -> bool {
+    matches!(card.suit(), Suit::Spades | Suit::Clubs)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{
+        rank::Rank,
+        rules::{MAX_LIFE_POINTS, ROOM_SIZE},
+    };
+
+    /// Puts `card` in the current room and plays it.
+    fn play(game: &mut Scoundrel, card: &Card) -> GameState {
+        game.room.push(*card);
+        game.play_card(card).expect("The card is in the room")
+    }
 
     #[test]
     fn new_scoundrel_has_44_cards() {
@@ -310,6 +1144,19 @@ mod tests {
         assert_eq!(game.deck.len(), 44);
     }
 
+    #[test]
+    fn same_seed_deals_the_same_game() {
+        let mut game = Scoundrel::from_seed(1234);
+        let mut same = Scoundrel::from_seed(game.seed());
+
+        assert_eq!(game.seed(), 1234);
+        assert_eq!(game.deck, same.deck);
+
+        game.enter_room().expect("First room");
+        same.enter_room().expect("First room");
+        assert_eq!(game.room(), same.room());
+    }
+
     #[test]
     fn new_scoundrel_has_20_life_points() {
         let game = Scoundrel::new();
@@ -324,18 +1171,106 @@ mod tests {
         assert_eq!(game.weapon_equipped, None);
     }
 
+    #[test]
+    fn game_with_rules_starts_with_their_life_and_deck() {
+        let rules = ScoundrelRules::builder()
+            .starting_life(12)
+            .max_life(30)
+            .banned_cards([])
+            .build()
+            .expect("Valid rules");
+        let mut game = Scoundrel::with_rules(rules);
+
+        assert_eq!(game.life_points(), 12);
+        assert_eq!(game.cards_left(), 52);
+
+        play(&mut game, &Card::new(Suit::Hearts, Rank::King));
+        assert_eq!(game.life_points(), 25);
+    }
+
+    #[test]
+    fn rules_set_the_room_size_and_the_cards_carried_over() {
+        let rules = ScoundrelRules::builder()
+            .room_size(6)
+            .carried_over(2)
+            .build()
+            .expect("Valid rules");
+        let mut game = Scoundrel::from_seed_with_rules(1, rules);
+        game.enter_room().expect("First room");
+        assert_eq!(game.room().len(), 6);
+        game.life_points = u8::MAX;
+
+        for _ in 0..4 {
+            let card = game.room()[0];
+            game.play_card_with(&card, CombatMode::Barehanded)
+                .expect("The card is in the room");
+        }
+
+        let carried = game.room_history()[0].cards()[4..].to_vec();
+        assert_eq!(game.rooms_visited(), 2);
+        assert_eq!(game.room().len(), 6);
+        assert_eq!(&game.room()[..2], carried.as_slice());
+        assert!(matches!(
+            game.journal().iter().rev().find(|event| matches!(event, Event::RoomEntered { .. })),
+            Some(Event::RoomEntered { carried: cards, .. }) if *cards == carried
+        ));
+    }
+
+    #[test]
+    fn rules_set_the_potions_per_room() {
+        let rules = ScoundrelRules::builder()
+            .potions_per_room(2)
+            .build()
+            .expect("Valid rules");
+        let mut game = Scoundrel::with_rules(rules);
+        game.life_points = 5;
+
+        play(&mut game, &Card::new(Suit::Hearts, Rank::Two));
+        play(&mut game, &Card::new(Suit::Hearts, Rank::Three));
+        play(&mut game, &Card::new(Suit::Hearts, Rank::Four));
+
+        assert_eq!(game.life_points(), 10);
+        assert_eq!(game.wasted_potions().len(), 1);
+    }
+
+    #[test]
+    fn rules_set_the_run_away_limit() {
+        let rules = ScoundrelRules::builder()
+            .run_away_limit(2)
+            .build()
+            .expect("Valid rules");
+        let mut game = Scoundrel::from_seed_with_rules(2, rules);
+
+        for _ in 0..2 {
+            game.enter_room().expect("A new room");
+            game.run_away().expect("Running away is allowed");
+        }
+        game.enter_room().expect("A new room");
+        assert_eq!(game.consecutive_run_aways(), 2);
+        assert_eq!(game.run_away(), Err(ScoundrelError::AlreadyRanAway));
+
+        let rules = ScoundrelRules::builder()
+            .run_away_limit(0)
+            .build()
+            .expect("Valid rules");
+        let mut game = Scoundrel::from_seed_with_rules(2, rules);
+        game.enter_room().expect("First room");
+        assert!(!game.is_legal(&Action::RunAway));
+    }
+
     #[test]
     fn new_scoundrel_has_0_room_visited() {
         let game = Scoundrel::new();
 
-        assert_eq!(game.room_visited, 0);
+        assert_eq!(game.rooms_visited(), 0);
+        assert!(game.room_history().is_empty());
     }
 
     #[test]
     fn monster_lowers_life_points() {
         let mut game = Scoundrel::new();
 
-        game.play_card(&Card::new(Suit::Spades, Rank::Five));
+        play(&mut game, &Card::new(Suit::Spades, Rank::Five));
 
         assert_eq!(game.life_points, 15);
     }
@@ -344,15 +1279,15 @@ mod tests {
     fn when_life_points_reach_0_game_over() {
         let mut game = Scoundrel::new();
 
-        let mut game_state = game.play_card(&Card::new(Suit::Spades, Rank::Five));
+        let mut game_state = play(&mut game, &Card::new(Suit::Spades, Rank::Five));
         assert_eq!(game.life_points, 15);
         assert_eq!(game_state, GameState::InGame);
 
-        game_state = game.play_card(&Card::new(Suit::Clubs, Rank::Five));
+        game_state = play(&mut game, &Card::new(Suit::Clubs, Rank::Five));
         assert_eq!(game.life_points, 10);
         assert_eq!(game_state, GameState::InGame);
 
-        game_state = game.play_card(&Card::new(Suit::Clubs, Rank::Ten));
+        game_state = play(&mut game, &Card::new(Suit::Clubs, Rank::Ten));
         assert_eq!(game.life_points, 0);
         assert_eq!(game_state, GameState::Lose);
     }
@@ -361,15 +1296,15 @@ mod tests {
     fn when_life_points_reach_0_game_over_underflow() {
         let mut game = Scoundrel::new();
 
-        let mut game_state = game.play_card(&Card::new(Suit::Spades, Rank::Five));
+        let mut game_state = play(&mut game, &Card::new(Suit::Spades, Rank::Five));
         assert_eq!(game.life_points, 15);
         assert_eq!(game_state, GameState::InGame);
 
-        game_state = game.play_card(&Card::new(Suit::Clubs, Rank::Five));
+        game_state = play(&mut game, &Card::new(Suit::Clubs, Rank::Five));
         assert_eq!(game.life_points, 10);
         assert_eq!(game_state, GameState::InGame);
 
-        game_state = game.play_card(&Card::new(Suit::Clubs, Rank::King));
+        game_state = play(&mut game, &Card::new(Suit::Clubs, Rank::King));
         assert_eq!(game.life_points, 0);
         assert_eq!(game_state, GameState::Lose);
     }
@@ -380,7 +1315,7 @@ mod tests {
 
         let weapon = Card::new(Suit::Diamonds, Rank::Nine);
 
-        game.play_card(&weapon);
+        play(&mut game, &weapon);
         assert_eq!(
             game.weapon_equipped().expect("Weapon just equipped").weapon,
             weapon
@@ -393,7 +1328,7 @@ mod tests {
 
         let weapon = Card::new(Suit::Diamonds, Rank::Nine);
 
-        game.play_card(&weapon);
+        play(&mut game, &weapon);
         assert_eq!(
             game.weapon_equipped().expect("Weapon just equipped").weapon,
             weapon
@@ -401,7 +1336,7 @@ mod tests {
 
         let new_weapon = Card::new(Suit::Diamonds, Rank::Five);
 
-        game.play_card(&new_weapon);
+        play(&mut game, &new_weapon);
         assert_eq!(
             game.weapon_equipped().expect("Weapon just equipped").weapon,
             new_weapon
@@ -418,14 +1353,14 @@ mod tests {
 
         let weapon = Card::new(Suit::Diamonds, Rank::Nine);
 
-        game.play_card(&weapon);
+        play(&mut game, &weapon);
         assert_eq!(
             game.weapon_equipped().expect("Weapon just equipped").weapon,
             weapon
         );
 
         let monster = Card::new(Suit::Clubs, Rank::Eight);
-        game.play_card(&monster);
+        play(&mut game, &monster);
         // Because the monster has lower rank compared to the weapon,
         // no life-points should be removed, but card should be saved on the weapon's
         // monster stack.
@@ -444,14 +1379,14 @@ mod tests {
 
         let weapon = Card::new(Suit::Diamonds, Rank::Nine);
 
-        game.play_card(&weapon);
+        play(&mut game, &weapon);
         assert_eq!(
             game.weapon_equipped().expect("Weapon just equipped").weapon,
             weapon
         );
 
         let monster = Card::new(Suit::Clubs, Rank::Jack);
-        game.play_card(&monster);
+        play(&mut game, &monster);
         // Because the monster has higher rank compared to the weapon,
         // `monster.rank` - `weapon.rank` () life-points should be removed,
         // life_points - (monster - weapon) = 20 - (11 - 9)
@@ -472,14 +1407,14 @@ mod tests {
 
         let weapon = Card::new(Suit::Diamonds, Rank::Nine);
 
-        game.play_card(&weapon);
+        play(&mut game, &weapon);
         assert_eq!(
             game.weapon_equipped().expect("Weapon just equipped").weapon,
             weapon
         );
 
         let monster = Card::new(Suit::Clubs, Rank::Two);
-        game.play_card(&monster);
+        play(&mut game, &monster);
         // Because the monster has lower rank compared to the weapon,
         // no life-points should be removed, but card should be saved on the weapon's
         // monster stack.
@@ -493,7 +1428,7 @@ mod tests {
         );
 
         let new_monster = Card::new(Suit::Clubs, Rank::Ten);
-        game.play_card(&new_monster);
+        play(&mut game, &new_monster);
         // Because the monster has higher rank compared to the weapon latest monster in the stack,
         // `new_monster` rank life-points should be removed, but card shouldn't be saved on the weapon's
         // monster stack. It is like the monster if fought barehanded.
@@ -501,19 +1436,126 @@ mod tests {
         assert_eq!(
             game.weapon_equipped
                 .expect("Weapon has just been equipped")
-                .defeated_monsters()
-                .as_ref(),
+                .defeated_monsters(),
             vec![monster]
         );
     }
 
+    #[test]
+    fn fight_a_monster_barehanded_spares_the_weapon() {
+        let mut game = Scoundrel::new();
+        let weapon = Card::new(Suit::Diamonds, Rank::Nine);
+        let monster = Card::new(Suit::Spades, Rank::Three);
+        play(&mut game, &weapon);
+
+        game.room.push(monster);
+        game.play_card_with(&monster, CombatMode::Barehanded)
+            .expect("The card is in the room");
+
+        assert_eq!(game.life_points(), 17);
+        assert!(game
+            .weapon_equipped()
+            .expect("Weapon still equipped")
+            .defeated_monsters()
+            .is_empty());
+    }
+
+    #[test]
+    fn fight_with_an_unusable_weapon_fails() {
+        let mut game = Scoundrel::new();
+        let monster = Card::new(Suit::Clubs, Rank::Six);
+        game.room.push(monster);
+
+        // No weapon equipped.
+        assert_eq!(
+            game.play_card_with(&monster, CombatMode::Weapon),
+            Err(ScoundrelError::CannotUseWeapon(monster))
+        );
+
+        // The weapon already slayed a weaker monster.
+        play(&mut game, &Card::new(Suit::Diamonds, Rank::Five));
+        play(&mut game, &Card::new(Suit::Spades, Rank::Four));
+        assert_eq!(
+            game.play_card_with(&monster, CombatMode::Weapon),
+            Err(ScoundrelError::CannotUseWeapon(monster))
+        );
+
+        // The failed action leaves the room and the character untouched.
+        assert!(game.room().contains(&monster));
+        assert_eq!(game.life_points(), 20);
+    }
+
+    #[test]
+    fn combat_preview_shows_the_damage_of_each_mode() {
+        let mut game = Scoundrel::new();
+        let monster = Card::new(Suit::Spades, Rank::Ten);
+
+        assert_eq!(
+            game.combat_preview(&monster),
+            Some(CombatPreview {
+                barehanded: 10,
+                weapon: None
+            })
+        );
+
+        play(&mut game, &Card::new(Suit::Diamonds, Rank::Seven));
+        assert_eq!(
+            game.combat_preview(&monster),
+            Some(CombatPreview {
+                barehanded: 10,
+                weapon: Some(3)
+            })
+        );
+
+        assert_eq!(
+            game.combat_preview(&Card::new(Suit::Hearts, Rank::Two)),
+            None
+        );
+    }
+
+    #[test]
+    fn ace_of_spades_is_the_strongest_monster_by_default() {
+        let mut game = Scoundrel::new();
+
+        play(&mut game, &Card::new(Suit::Spades, Rank::Ace));
+
+        assert_eq!(game.life_points(), 6);
+    }
+
+    #[test]
+    fn ace_of_spades_deals_1_damage_when_aces_are_low() {
+        let mut game = Scoundrel::with_rank_mode(RankMode::AceLow);
+
+        play(&mut game, &Card::new(Suit::Clubs, Rank::Ace));
+
+        assert_eq!(game.life_points(), 19);
+    }
+
+    #[test]
+    fn weapon_stack_compares_aces_with_the_rank_mode() {
+        let ace = Card::new(Suit::Spades, Rank::Ace);
+        let king = Card::new(Suit::Clubs, Rank::King);
+
+        // Aces high: a King can be slain after an Ace.
+        let mut game = Scoundrel::new();
+        play(&mut game, &Card::new(Suit::Diamonds, Rank::Ten));
+        play(&mut game, &ace);
+        assert!(game.can_use_weapon(&king));
+
+        // Aces low: nothing is weaker than an Ace.
+        let mut game = Scoundrel::with_rank_mode(RankMode::AceLow);
+        play(&mut game, &Card::new(Suit::Diamonds, Rank::Ten));
+        play(&mut game, &ace);
+        assert!(!game.can_use_weapon(&king));
+    }
+
     #[test]
     fn playing_heart_cards_heals_life_points() {
         let mut game = Scoundrel::new();
 
         game.life_points = 15;
 
-        game.play_card(&Card::new(Suit::Hearts, Rank::Five));
+        play(&mut game, &Card::new(Suit::Hearts, Rank::Five));
         assert_eq!(game.life_points, MAX_LIFE_POINTS);
     }
 
@@ -522,21 +1564,171 @@ mod tests {
         let mut game = Scoundrel::new();
 
         assert_eq!(game.life_points, MAX_LIFE_POINTS);
-        game.play_card(&Card::new(Suit::Hearts, Rank::Five));
+        play(&mut game, &Card::new(Suit::Hearts, Rank::Five));
         assert_eq!(game.life_points, MAX_LIFE_POINTS);
     }
 
+    #[test]
+    fn only_one_potion_heals_per_room() {
+        let mut game = Scoundrel::new();
+        let first = Card::new(Suit::Hearts, Rank::Four);
+        let second = Card::new(Suit::Hearts, Rank::Six);
+        game.life_points = 5;
+
+        play(&mut game, &first);
+        assert_eq!(game.life_points(), 9);
+        assert_eq!(game.potions_used_in_room(), 1);
+        assert!(!game
+            .journal()
+            .iter()
+            .any(|event| matches!(event, Event::PotionWasted { .. })));
+
+        play(&mut game, &second);
+        assert_eq!(game.life_points(), 9);
+        assert_eq!(game.wasted_potions(), &[second]);
+        assert!(game
+            .journal()
+            .contains(&Event::PotionWasted { potion: second }));
+    }
+
+    #[test]
+    fn potion_limit_resets_in_a_new_room() {
+        let mut game = Scoundrel::new();
+        let potion = Card::new(Suit::Hearts, Rank::Four);
+        game.life_points = 5;
+
+        play(&mut game, &potion);
+        assert_eq!(game.potions_used_in_room(), 1);
+
+        game.enter_room().expect("The room is empty");
+        assert_eq!(game.potions_used_in_room(), 0);
+    }
+
+    #[test]
+    fn no_score_while_the_game_is_in_progress() {
+        let mut game = Scoundrel::from_seed(1);
+        game.enter_room().expect("First room");
+
+        assert_eq!(game.score(), None);
+        assert_eq!(game.result(), None);
+    }
+
+    #[test]
+    fn losing_score_subtracts_the_monsters_left() {
+        let mut game = Scoundrel::from_seed(1);
+        game.enter_room().expect("First room");
+        game.life_points = 1;
+
+        // Sum of the monsters still in the dungeon, the killer excluded.
+        let killer = Card::new(Suit::Spades, Rank::Two);
+        let monsters: u32 = game
+            .deck
+            .iter()
+            .chain(game.room.iter())
+            .filter(|card| is_monster(card) && **card != killer)
+            .map(|card| u32::from(RankMode::AceHigh.value(card.rank())))
+            .sum();
+
+        game.room.retain(|card| *card != killer);
+        let _ = game.deck.pull(&killer);
+        assert_eq!(play(&mut game, &killer), GameState::Lose);
+
+        let result = game.result().expect("The game is over");
+        assert_eq!(result.state, GameState::Lose);
+        assert_eq!(result.monsters_left, monsters);
+        assert_eq!(result.score, -(monsters as i32));
+        assert_eq!(game.score(), Some(-(monsters as i32)));
+    }
+
+    #[test]
+    fn winning_score_adds_the_last_potion() {
+        let mut game = Scoundrel::new();
+        let potion = Card::new(Suit::Hearts, Rank::Six);
+        game.life_points = 10;
+
+        play(&mut game, &potion);
+        let mut room = RoomRecord::new(vec![potion]);
+        room.played.push(potion);
+        Arc::make_mut(&mut game.rooms).push(room);
+        game.state = GameState::Win;
+
+        let result = game.result().expect("The game is over");
+        assert_eq!(result.score, 16 + 6);
+        assert_eq!(result.last_potion, Some(potion));
+    }
+
+    #[test]
+    fn winning_score_is_the_life_left_when_the_last_card_is_not_a_potion() {
+        let mut game = Scoundrel::new();
+        let monster = Card::new(Suit::Clubs, Rank::Three);
+        let mut room = RoomRecord::new(vec![monster]);
+        room.played.push(monster);
+        Arc::make_mut(&mut game.rooms).push(room);
+        game.life_points = 12;
+        game.state = GameState::Win;
+
+        assert_eq!(game.score(), Some(12));
+    }
+
     #[test]
     fn player_can_run_away_from_a_room_per_time() {
         let mut game = Scoundrel::new();
 
-        game.enter_room();
+        game.enter_room().expect("First room");
         let res = game.run_away();
 
         assert!(res.is_ok());
     
         let res = game.run_away();
-        assert!(res.is_err()) 
+        assert_eq!(res, Err(ScoundrelError::AlreadyRanAway));
+
+        // Still not allowed in the next room.
+        game.enter_room().expect("The room has been fled");
+        let res = game.run_away();
+        assert_eq!(res, Err(ScoundrelError::AlreadyRanAway));
+    }
+
+    #[test]
+    fn player_can_run_away_again_after_clearing_a_room() {
+        let mut game = Scoundrel::new();
+        game.enter_room().expect("First room");
+        game.run_away().expect("First run away");
+
+        game.enter_room().expect("The room has been fled");
+        assert!(game.has_run_away());
+        game.room.truncate(1);
+        game.enter_room()
+            .expect("A single card is left in the room");
+
+        assert!(!game.has_run_away());
+        assert_eq!(game.run_away(), Ok(()));
+    }
+
+    #[test]
+    fn room_history_records_entered_fled_and_cleared_rooms() {
+        let mut game = Scoundrel::new();
+        game.enter_room().expect("First room");
+        let fled = game.room().to_vec();
+        game.run_away().expect("First run away");
+
+        game.enter_room().expect("The room has been fled");
+        let played: Vec<Card> = game.room()[..3].to_vec();
+        for card in &played {
+            // Potions can't heal over the maximum: survive every monster.
+            game.life_points = MAX_LIFE_POINTS;
+            game.play_card_with(card, CombatMode::Barehanded)
+                .expect("The card is in the room");
+        }
+
+        let history = game.room_history();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].cards(), fled.as_slice());
+        assert_eq!(history[0].status(), RoomStatus::Fled);
+        assert!(history[0].played().is_empty());
+        assert_eq!(history[1].status(), RoomStatus::Cleared);
+        assert_eq!(history[1].played(), played.as_slice());
+        assert_eq!(history[2].status(), RoomStatus::Entered);
+        assert_eq!(history[2].cards(), game.room());
     }
 
     #[test]
@@ -545,7 +1737,451 @@ mod tests {
 
         let res = game.run_away();
 
-        assert!(res.is_err()) 
+        assert_eq!(res, Err(ScoundrelError::NotInRoom))
+    }
+
+    #[test]
+    fn player_cannot_run_away_from_a_started_room() {
+        let mut game = Scoundrel::new();
+        game.enter_room().expect("First room");
+
+        let card = game.room()[0];
+        game.play_card(&card).expect("The card is in the room");
+
+        assert_eq!(game.run_away(), Err(ScoundrelError::RoomAlreadyStarted));
+    }
+
+    #[test]
+    fn entering_an_unfinished_room_fails() {
+        let mut game = Scoundrel::new();
+        game.enter_room().expect("First room");
+
+        assert_eq!(
+            game.enter_room(),
+            Err(ScoundrelError::RoomNotFinished { cards_left: 4 })
+        );
+    }
+
+    #[test]
+    fn entering_a_room_carries_over_the_last_card() {
+        let mut game = Scoundrel::new();
+        let last_card = Card::new(Suit::Hearts, Rank::Two);
+        game.room.push(last_card);
+
+        game.enter_room()
+            .expect("A single card is left in the room");
+
+        assert_eq!(game.room().len(), ROOM_SIZE);
+        assert_eq!(game.room()[0], last_card);
+        assert_eq!(game.cards_left(), 41);
+    }
+
+    #[test]
+    fn playing_a_card_not_in_the_room_fails() {
+        let mut game = Scoundrel::new();
+        let card = Card::new(Suit::Spades, Rank::Five);
+
+        assert_eq!(
+            game.play_card(&card),
+            Err(ScoundrelError::CardNotInRoom(card))
+        );
+        assert_eq!(game.life_points, MAX_LIFE_POINTS);
+    }
+
+    #[test]
+    fn played_card_is_removed_from_the_room() {
+        let mut game = Scoundrel::new();
+        game.enter_room().expect("First room");
+
+        let card = game.room()[2];
+        game.play_card(&card).expect("The card is in the room");
+
+        assert_eq!(game.room().len(), 3);
+        assert!(!game.room().contains(&card));
+        assert_eq!(
+            game.play_card(&card),
+            Err(ScoundrelError::CardNotInRoom(card))
+        );
+    }
+
+    #[test]
+    fn playing_three_cards_enters_the_next_room() {
+        let mut game = Scoundrel::new();
+        let room = [
+            Card::new(Suit::Diamonds, Rank::Two),
+            Card::new(Suit::Hearts, Rank::Three),
+            Card::new(Suit::Diamonds, Rank::Four),
+            Card::new(Suit::Hearts, Rank::Five),
+        ];
+        game.enter_room().expect("First room");
+        game.room = room.to_vec();
+
+        game.play_card(&room[0]).expect("The card is in the room");
+        game.play_card(&room[1]).expect("The card is in the room");
+        assert_eq!(game.rooms_visited(), 1);
+        assert_eq!(game.room().len(), 2);
+
+        let state = game.play_card(&room[3]).expect("The card is in the room");
+
+        // The last card is carried over to the new room.
+        assert_eq!(state, GameState::InGame);
+        assert_eq!(game.rooms_visited(), 2);
+        assert_eq!(game.room().len(), ROOM_SIZE);
+        assert_eq!(game.room()[0], room[2]);
+    }
+
+    #[test]
+    fn rooms_remaining_follows_the_deck() {
+        let mut game = Scoundrel::from_seed(2);
+
+        // 44 cards: 4 in the first room, then 3 per room and 1 in the last one.
+        assert_eq!(game.rooms_remaining(), 15);
+        game.enter_room().expect("First room");
+        assert_eq!(game.rooms_remaining(), 14);
+
+        // Fled cards go back under the deck.
+        game.run_away().expect("First run away");
+        assert_eq!(game.rooms_remaining(), 15);
+    }
+
+    #[test]
+    fn game_is_won_when_the_deck_is_empty_and_the_last_room_resolved() {
+        let mut game = Scoundrel::from_seed(9);
+        game.enter_room().expect("First room");
+
+        while game.state() == GameState::InGame {
+            // Enough life to survive the whole dungeon.
+            game.life_points = 200;
+            let card = game.room()[0];
+            game.play_card_with(&card, CombatMode::Barehanded)
+                .expect("The card is in the room");
+        }
+
+        assert_eq!(game.state(), GameState::Win);
+        assert_eq!(game.cards_left(), 0);
+        assert!(game.room().is_empty());
+        assert_eq!(game.rooms_visited(), 15);
+        assert_eq!(game.rooms_remaining(), 0);
+        // The last room has only 2 cards: 1 carried over and 1 drawn.
+        let last_room = game.room_history().last().expect("Rooms visited");
+        assert_eq!(last_room.cards().len(), 2);
+        assert_eq!(last_room.status(), RoomStatus::Cleared);
+    }
+
+    #[test]
+    fn last_card_of_the_dungeon_is_not_carried_over() {
+        let mut game = Scoundrel::new();
+        let card = Card::new(Suit::Hearts, Rank::Two);
+        game.deck.draw(44).expect("Full deck");
+        game.room.push(card);
+
+        assert_eq!(
+            game.enter_room(),
+            Err(ScoundrelError::RoomNotFinished { cards_left: 1 })
+        );
+        assert_eq!(game.play_card(&card), Ok(GameState::Win));
+    }
+
+    #[test]
+    fn legal_actions_at_the_beginning_of_the_game() {
+        let game = Scoundrel::from_seed(4);
+
+        assert_eq!(game.legal_actions(), vec![Action::EnterRoom]);
+    }
+
+    #[test]
+    fn legal_actions_in_a_room() {
+        let mut game = Scoundrel::from_seed(4);
+        game.enter_room().expect("First room");
+        let weapon = Card::new(Suit::Diamonds, Rank::Five);
+        let potion = Card::new(Suit::Hearts, Rank::Three);
+        let monster = Card::new(Suit::Spades, Rank::Nine);
+        let weaker_monster = Card::new(Suit::Clubs, Rank::Two);
+        game.room = vec![weapon, potion, monster, weaker_monster];
+
+        assert_eq!(
+            game.legal_actions(),
+            vec![
+                Action::PlayCard(weapon),
+                Action::PlayCard(potion),
+                Action::Fight(monster, CombatMode::Barehanded),
+                Action::Fight(weaker_monster, CombatMode::Barehanded),
+                Action::RunAway,
+            ]
+        );
+
+        game.apply(Action::PlayCard(weapon))
+            .expect("The weapon is in the room");
+        assert_eq!(
+            game.legal_actions(),
+            vec![
+                Action::PlayCard(potion),
+                Action::Fight(monster, CombatMode::Weapon),
+                Action::Fight(monster, CombatMode::Barehanded),
+                Action::Fight(weaker_monster, CombatMode::Weapon),
+                Action::Fight(weaker_monster, CombatMode::Barehanded),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_legal_action_can_be_applied() {
+        let mut game = Scoundrel::from_seed(21);
+
+        while game.state() == GameState::InGame {
+            let actions = game.legal_actions();
+            assert!(!actions.is_empty());
+            for action in &actions {
+                assert!(game.is_legal(action), "{action} should be legal");
+                assert!(game.clone().apply(*action).is_ok(), "{action} failed");
+            }
+            game.life_points = 200;
+            // Prefer fleeing, to cover both fleeing and clearing rooms.
+            let action = actions
+                .iter()
+                .find(|action| **action == Action::RunAway)
+                .unwrap_or(&actions[0]);
+            game.apply(*action).expect("The action is legal");
+        }
+        assert_eq!(game.state(), GameState::Win);
+        assert!(game.legal_actions().is_empty());
+    }
+
+    #[test]
+    fn illegal_actions_are_rejected() {
+        let mut game = Scoundrel::from_seed(4);
+        let monster = Card::new(Suit::Spades, Rank::Nine);
+
+        assert!(!game.is_legal(&Action::RunAway));
+        assert_eq!(game.apply(Action::RunAway), Err(ScoundrelError::NotInRoom));
+
+        game.enter_room().expect("First room");
+        game.room[0] = monster;
+        assert!(!game.is_legal(&Action::EnterRoom));
+        assert!(!game.is_legal(&Action::PlayCard(monster)));
+        assert_eq!(
+            game.apply(Action::Fight(monster, CombatMode::Weapon)),
+            Err(ScoundrelError::CannotUseWeapon(monster))
+        );
+    }
+
+    #[test]
+    fn apply_rejects_a_monster_played_without_a_combat_mode() {
+        let mut game = Scoundrel::from_seed(4);
+        game.enter_room().expect("First room");
+        let monster = Card::new(Suit::Spades, Rank::Nine);
+        game.room[0] = monster;
+        let before = game.clone();
+
+        assert!(!game.is_legal(&Action::PlayCard(monster)));
+        assert_eq!(
+            game.apply(Action::PlayCard(monster)),
+            Err(ScoundrelError::WrongActionForCard(monster))
+        );
+        assert_eq!(game.room(), before.room());
+        assert_eq!(game.life_points(), before.life_points());
+    }
+
+    #[test]
+    fn apply_rejects_fighting_a_card_that_is_not_a_monster() {
+        let mut game = Scoundrel::from_seed(4);
+        game.enter_room().expect("First room");
+        let potion = Card::new(Suit::Hearts, Rank::Three);
+        game.room[0] = potion;
+        game.life_points = 10;
+
+        for mode in [CombatMode::Weapon, CombatMode::Barehanded] {
+            assert!(!game.is_legal(&Action::Fight(potion, mode)));
+            assert_eq!(
+                game.apply(Action::Fight(potion, mode)),
+                Err(ScoundrelError::WrongActionForCard(potion))
+            );
+        }
+        assert_eq!(game.life_points(), 10);
+        assert!(game.room().contains(&potion));
+    }
+
+    #[test]
+    fn apply_fails_exactly_for_the_illegal_actions() {
+        let mut game = Scoundrel::from_seed(9);
+        game.enter_room().expect("First room");
+        let mut actions = vec![Action::RunAway, Action::EnterRoom];
+        for card in game.room() {
+            actions.push(Action::PlayCard(*card));
+            actions.push(Action::Fight(*card, CombatMode::Weapon));
+            actions.push(Action::Fight(*card, CombatMode::Barehanded));
+        }
+
+        for action in actions {
+            let applied = game.clone().apply(action);
+            assert_eq!(applied.is_ok(), game.is_legal(&action), "{action}");
+        }
+    }
+
+    #[test]
+    fn undo_a_weapon_swap_restores_the_monster_stack() {
+        let mut game = Scoundrel::from_seed(8);
+        game.enter_room().expect("First room");
+        let weapon = Card::new(Suit::Diamonds, Rank::Eight);
+        let monster = Card::new(Suit::Spades, Rank::Six);
+        let new_weapon = Card::new(Suit::Diamonds, Rank::Three);
+        game.room = vec![
+            weapon,
+            monster,
+            new_weapon,
+            Card::new(Suit::Hearts, Rank::Two),
+        ];
+
+        game.play_card(&weapon).expect("The card is in the room");
+        game.play_card(&monster).expect("The card is in the room");
+        let before_swap = game.weapon_equipped().cloned();
+
+        game.play_card(&new_weapon)
+            .expect("The card is in the room");
+        assert_eq!(game.rooms_visited(), 2);
+
+        // The swap and the room entered after it are undone together.
+        game.undo().expect("An action to undo");
+        assert_eq!(game.weapon_equipped().cloned(), before_swap);
+        assert_eq!(
+            game.weapon_equipped()
+                .expect("Weapon restored")
+                .defeated_monsters(),
+            vec![monster]
+        );
+        assert_eq!(game.rooms_visited(), 1);
+        assert!(game.room().contains(&new_weapon));
+    }
+
+    #[test]
+    fn undo_a_run_away_takes_the_cards_back_from_the_deck() {
+        let mut game = Scoundrel::from_seed(8);
+        game.enter_room().expect("First room");
+        let room = game.room().to_vec();
+        let deck = game.deck.clone();
+
+        game.run_away().expect("First run away");
+        game.undo().expect("An action to undo");
+
+        assert_eq!(game.room(), room.as_slice());
+        assert_eq!(game.deck, deck);
+        assert!(!game.has_run_away());
+        assert_eq!(game.room_history()[0].status(), RoomStatus::Entered);
+        assert_eq!(game.journal().len(), 1);
+    }
+
+    #[test]
+    fn undo_the_losing_move() {
+        let mut game = Scoundrel::from_seed(8);
+        game.enter_room().expect("First room");
+        game.life_points = 1;
+        let monster = Card::new(Suit::Clubs, Rank::Nine);
+        game.room[0] = monster;
+
+        assert_eq!(game.play_card(&monster), Ok(GameState::Lose));
+        assert_eq!(game.undo(), Ok(GameState::InGame));
+        assert_eq!(game.life_points(), 1);
+    }
+
+    #[test]
+    fn redo_an_undone_action() {
+        let mut game = Scoundrel::from_seed(8);
+        game.enter_room().expect("First room");
+        game.run_away().expect("First run away");
+        let after = game.journal().to_vec();
+
+        game.undo().expect("An action to undo");
+        game.undo().expect("An action to undo");
+        assert_eq!(game.rooms_visited(), 0);
+        assert_eq!(game.undo(), Err(ScoundrelError::NothingToUndo));
+
+        game.redo().expect("An action to redo");
+        game.redo().expect("An action to redo");
+        assert_eq!(game.journal(), after.as_slice());
+        assert_eq!(game.redo(), Err(ScoundrelError::NothingToRedo));
+    }
+
+    #[test]
+    fn a_new_action_clears_the_redo_stack() {
+        let mut game = Scoundrel::from_seed(8);
+        game.enter_room().expect("First room");
+        game.run_away().expect("First run away");
+
+        game.undo().expect("An action to undo");
+        assert!(game.can_redo());
+        let card = game.room()[0];
+        game.play_card_with(&card, CombatMode::Barehanded)
+            .expect("The card is in the room");
+
+        assert!(!game.can_redo());
+        assert!(game.can_undo());
+    }
+
+    #[test]
+    fn undo_can_be_disabled() {
+        let mut game = Scoundrel::from_seed(8).without_undo();
+        game.enter_room().expect("First room");
+
+        assert!(!game.undo_enabled());
+        assert!(!game.can_undo());
+        assert_eq!(game.undo(), Err(ScoundrelError::UndoDisabled));
+        assert_eq!(game.redo(), Err(ScoundrelError::UndoDisabled));
+    }
+
+    #[test]
+    fn clones_share_the_history_of_the_game() {
+        let mut game = Scoundrel::from_seed(8);
+        game.enter_room().expect("First room");
+        game.run_away().expect("First run away");
+        game.enter_room().expect("Next room");
+
+        let journal = game.journal().len();
+        let mut copy = game.clone();
+        assert!(Arc::ptr_eq(&game.rooms, &copy.rooms));
+        assert!(Arc::ptr_eq(&game.journal, &copy.journal));
+        assert!(Arc::ptr_eq(&game.undo_stack, &copy.undo_stack));
+
+        let card = copy.room()[0];
+        copy.play_card_with(&card, CombatMode::Barehanded)
+            .expect("The card is in the room");
+        assert!(copy.journal().len() > journal);
+        assert_eq!(game.journal().len(), journal);
+        assert!(!Arc::ptr_eq(&game.journal, &copy.journal));
+        assert_eq!(game.undo_stack.len(), 3);
+    }
+
+    #[test]
+    fn shuffle_unseen_keeps_the_cards_seen() {
+        let mut game = Scoundrel::from_seed(4);
+        game.enter_room().expect("First room");
+        let fled = game.room().to_vec();
+        game.run_away().expect("First run away");
+        game.enter_room().expect("Next room");
+        let original = game.clone();
+
+        game.shuffle_unseen_with(&mut ChaCha8Rng::seed_from_u64(1));
+
+        let deck: Vec<Card> = game.deck.iter().copied().collect();
+        let original_deck: Vec<Card> = original.deck.iter().copied().collect();
+        assert_eq!(game.room(), original.room());
+        assert_eq!(deck[deck.len() - fled.len()..], fled[..]);
+        assert_ne!(deck, original_deck);
+        assert!(original_deck.iter().all(|card| deck.contains(card)));
+    }
+
+    #[test]
+    fn no_action_is_allowed_after_the_game_is_over() {
+        let mut game = Scoundrel::new();
+        let monster = Card::new(Suit::Spades, Rank::King);
+        game.life_points = 5;
+
+        assert_eq!(play(&mut game, &monster), GameState::Lose);
+        assert_eq!(game.state(), GameState::Lose);
+
+        game.room.push(monster);
+        assert_eq!(game.play_card(&monster), Err(ScoundrelError::GameOver));
+        assert_eq!(game.enter_room(), Err(ScoundrelError::GameOver));
+        assert_eq!(game.run_away(), Err(ScoundrelError::GameOver));
     }
 
 }
@@ -0,0 +1,581 @@
+//! Exhaustive solver of Scoundrel deals.
+//!
+//! Once the deck is dealt, Scoundrel has no hidden information: the solver
+//! explores every line of play (play order, weapon or barehanded, run away
+//! or stay) and keeps the best score. Positions reached through different
+//! lines are searched once, thanks to a memo on a canonical key: the
+//! `Layout` of the cards and the `Resources` of the character.
+//!
+//! The lines that can't beat the best one found so far are cut, when the
+//! cards left can't make up for it or when a position with more resources
+//! already did no better. A standard deal is still a big search: a few
+//! million positions, searched in about 4 µs and kept in about 100 bytes
+//! each in a release build. `solve_within` gives up past a number of
+//! positions.
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    card::Card,
+    card_set::CardSet,
+    scoundrel::{Action, GameState, Scoundrel},
+    suit::Suit,
+};
+
+/// The outcome of the best line of play of a deal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    /// Whether the deal can be won.
+    pub winnable: bool,
+    /// The best score that can be reached, see `Scoundrel::score`.
+    pub best_score: i32,
+    /// One line of play reaching the best score, from the given position.
+    pub line: Vec<Action>,
+    /// Number of distinct positions searched, game overs and cut lines
+    /// excluded.
+    pub positions: usize,
+}
+
+/// Finds the best line of play from the current position of `game`.
+///
+/// The game itself is left untouched: the search plays on copies. The
+/// search of a standard deal takes seconds and hundreds of MB, see
+/// `solve_within` to bound it.
+///
+/// # Examples
+/// ```
+/// use dungeon_and_cards::{solve, CardSet, Scoundrel, ScoundrelRules, Suit};
+///
+/// // A short dungeon of Clubs and Diamonds only, quick to solve.
+/// let banned = CardSet::full()
+///     .iter()
+///     .filter(|card| matches!(card.suit(), Suit::Spades | Suit::Hearts));
+/// let rules = ScoundrelRules::builder().banned_cards(banned).build().unwrap();
+/// let game = Scoundrel::from_seed_with_rules(42, rules);
+/// let solution = solve(&game);
+///
+/// let mut replay = game.clone();
+/// for action in &solution.line {
+///     replay.apply(*action).unwrap();
+/// }
+/// assert_eq!(replay.score(), Some(solution.best_score));
+/// ```
+pub fn solve(game: &Scoundrel) -> Solution {
+    solve_within(game, usize::MAX).expect("The search has no limit")
+}
+
+/// Same as `solve`, giving up once `max_positions` positions have been
+/// searched: each one takes about 100 bytes of memory.
+///
+/// # Returns
+/// `None` if the search needs more positions: whether the deal can be won
+/// is unknown.
+///
+/// # Examples
+/// ```
+/// use dungeon_and_cards::{solve_within, Scoundrel};
+///
+/// // A standard deal needs millions of positions.
+/// let game = Scoundrel::from_seed(42);
+/// assert_eq!(solve_within(&game, 1000), None);
+/// ```
+pub fn solve_within(game: &Scoundrel, max_positions: usize) -> Option<Solution> {
+    // Snapshots are useless to the search, and costly.
+    let mut game = game.clone().without_undo();
+    trim(&mut game);
+    let mut search = Search {
+        memo: HashMap::new(),
+        positions: 0,
+        max_positions,
+        exhausted: false,
+    };
+    let (best_score, state) = search.best(&game, i32::MIN);
+    if search.exhausted {
+        return None;
+    }
+
+    // Follow the best actions recorded in the memo.
+    let mut line = Vec::new();
+    let mut position = game;
+    while let Some(action) = search.best_action(&position) {
+        position
+            .apply(action)
+            .expect("The memo only holds legal actions");
+        line.push(action);
+    }
+
+    Some(Solution {
+        winnable: state == GameState::Win,
+        best_score,
+        line,
+        positions: search.positions,
+    })
+}
+
+/// Drops the history of a game the search doesn't need: the journal, and
+/// the rooms before the ones that tell how many rooms were fled in a row.
+fn trim(game: &mut Scoundrel) {
+    game.journal = Arc::default();
+    let kept = game.rules().run_away_limit() + 2;
+    let old = game.rooms.len().saturating_sub(kept);
+    Arc::make_mut(&mut game.rooms).drain(..old);
+}
+
+/// The best outcome found from a position.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    /// Best score reachable, or an upper bound of it if not `exact`.
+    score: i32,
+    /// Whether `score` is the best score, or only an upper bound.
+    exact: bool,
+    /// How the game ends with that score.
+    state: GameState,
+    /// First action of the best line.
+    action: Option<Action>,
+}
+
+/// Depth-first branch and bound search, with a memo of the positions
+/// already searched.
+struct Search {
+    /// Positions searched, by layout of their cards.
+    memo: HashMap<Layout, Vec<(Resources, Node)>>,
+    /// Number of positions in the memo.
+    positions: usize,
+    /// Number of positions the memo can hold.
+    max_positions: usize,
+    /// Whether the search gave up, the memo being full.
+    exhausted: bool,
+}
+
+impl Search {
+    /// Returns the best score reachable from `game`, and how the game ends,
+    /// if that score is above `floor`. Otherwise the lines of play are cut
+    /// as soon as they can't beat `floor`, and only an upper bound of the
+    /// score, at most `floor`, is returned.
+    fn best(&mut self, game: &Scoundrel, floor: i32) -> (i32, GameState) {
+        // Not memoized: the bonus of a won game depends on its last card,
+        // which is not part of the key.
+        if let Some(score) = game.score() {
+            return (score, game.state());
+        }
+        let layout = Layout::new(game);
+        let resources = Resources::new(game);
+        // Lines scoring less than the best line of a position with fewer
+        // resources can be cut.
+        let mut floor_left = floor;
+        for (known, node) in self.memo.get(&layout).into_iter().flatten() {
+            if *known == resources && (node.exact || node.score <= floor) {
+                return (node.score, node.state);
+            }
+            // With fewer resources, the score can't be any better.
+            if known.covers(&resources) && node.score <= floor {
+                return (node.score, GameState::Lose);
+            }
+            if node.exact && resources.covers(known) {
+                floor_left = floor_left.max(node.score - 1);
+            }
+        }
+        let bound = upper_bound(game);
+        if bound <= floor {
+            return (bound, GameState::Lose);
+        }
+
+        let mut node = Node {
+            score: i32::MIN,
+            exact: false,
+            state: GameState::Lose,
+            action: None,
+        };
+        for action in game.legal_actions() {
+            let mut next = game.clone();
+            next.apply(action).expect("The action is legal");
+            trim(&mut next);
+            let (score, state) = self.best(&next, floor_left);
+            if self.exhausted {
+                return (i32::MIN, GameState::Lose);
+            }
+            if score > node.score {
+                node.score = score;
+                node.state = state;
+                node.action = Some(action);
+            }
+            // The other lines have to beat this one.
+            floor_left = floor_left.max(score);
+            if score >= bound {
+                break;
+            }
+        }
+
+        // Only lines scoring above `floor` are searched to the end.
+        node.exact = node.score > floor;
+        let nodes = self.memo.entry(layout).or_default();
+        match nodes.iter_mut().find(|(known, _)| *known == resources) {
+            Some((_, known_node)) => *known_node = node,
+            None if self.positions >= self.max_positions => {
+                self.exhausted = true;
+                return (i32::MIN, GameState::Lose);
+            }
+            None => {
+                nodes.push((resources, node));
+                self.positions += 1;
+            }
+        }
+        (node.score, node.state)
+    }
+
+    /// Returns the first action of the best line from a position searched
+    /// to the end, `None` once the game is over.
+    fn best_action(&self, game: &Scoundrel) -> Option<Action> {
+        let resources = Resources::new(game);
+        self.memo
+            .get(&Layout::new(game))?
+            .iter()
+            .find(|(known, node)| *known == resources && node.exact)
+            .and_then(|(_, node)| node.action)
+    }
+}
+
+/// Returns a score that no line of play from `game` can beat.
+///
+/// To win, every monster left has to be fought: the ones stronger than the
+/// best weapon left deal damage anyway, and potions can't heal over the
+/// maximum life points. The last potion played adds its value to the score.
+///
+/// If that damage is too much, the game is lost: the monsters that can be
+/// slain before dying deal at most the life points and the heal left, plus
+/// the blow that kills. The others are left in the dungeon.
+fn upper_bound(game: &Scoundrel) -> i32 {
+    let rules = game.rules();
+    let value = |card: &Card| i32::from(rules.rank_mode().value(card.rank()));
+    let cards = || game.deck.iter().chain(game.room());
+
+    let best_weapon = cards()
+        .filter(|card| card.suit() == Suit::Diamonds)
+        .chain(game.weapon_equipped().map(|weapon| &weapon.weapon))
+        .map(value)
+        .max()
+        .unwrap_or(0);
+    let damages = || {
+        cards()
+            .filter(|card| matches!(card.suit(), Suit::Spades | Suit::Clubs))
+            .map(|card| (value(card) - best_weapon).max(0))
+    };
+    let damage: i32 = damages().sum();
+    let potions = || {
+        cards()
+            .filter(|card| card.suit() == Suit::Hearts)
+            .map(value)
+    };
+    let heal: i32 = potions().sum();
+
+    let life = i32::from(game.life_points()) + heal - damage;
+    if life <= 0 {
+        // Monsters left are worth at least the damage they would deal.
+        let killing_blow = damages().max().unwrap_or(0);
+        return (life + killing_blow - 1).min(0);
+    }
+    life.min(i32::from(rules.max_life())) + potions().max().unwrap_or(0)
+}
+
+/// Where the cards of a game in progress are: the positions with the same
+/// layout only differ by the `Resources` of the character.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Layout {
+    /// Cards left in the deck, in order.
+    deck: Vec<Card>,
+    /// Cards of the room.
+    room: Room,
+}
+
+/// Cards of a room, as a set once their order doesn't matter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Room {
+    Ordered(Vec<Card>),
+    Unordered(CardSet),
+}
+
+impl Layout {
+    fn new(game: &Scoundrel) -> Self {
+        let rules = game.rules();
+        // The order of a fresh room is kept when it is fled, under the deck.
+        // Once started, only the cards carried over keep an order: it matters
+        // if more than one card is carried to the next room.
+        let room = if game.room().len() < rules.room_size() && rules.carried_over() <= 1 {
+            Room::Unordered(game.room().iter().collect())
+        } else {
+            Room::Ordered(game.room().to_vec())
+        };
+
+        Self {
+            deck: game.deck.iter().copied().collect(),
+            room,
+        }
+    }
+}
+
+/// What the character has left to go through the dungeon. With the same
+/// `Layout`, two positions with the same resources have the same best score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Resources {
+    life_points: u8,
+    /// Value of the weapon, and the value monsters must be under to be
+    /// slain with it: only the latest monster it slew limits it.
+    weapon: Option<(u8, u8)>,
+    /// Potions that can still heal in the room.
+    potions_left: usize,
+    /// Rooms that can still be fled in a row.
+    run_aways_left: usize,
+}
+
+impl Resources {
+    fn new(game: &Scoundrel) -> Self {
+        let rules = game.rules();
+        let value = |card: &Card| rules.rank_mode().value(card.rank());
+
+        Self {
+            life_points: game.life_points(),
+            weapon: game.weapon_equipped().and_then(|weapon| {
+                let limit = weapon.defeated_monsters.last().map_or(u8::MAX, value);
+                // Only the monsters left that it can slay tell weapons apart:
+                // a weapon that can't slay any is as good as none.
+                let strongest = game
+                    .deck
+                    .iter()
+                    .chain(game.room())
+                    .filter(|card| matches!(card.suit(), Suit::Spades | Suit::Clubs))
+                    .map(value)
+                    .filter(|&monster| monster < limit)
+                    .max()?;
+                Some((value(&weapon.weapon).min(strongest), strongest + 1))
+            }),
+            potions_left: rules
+                .potions_per_room()
+                .saturating_sub(game.potions_used_in_room()),
+            run_aways_left: rules
+                .run_away_limit()
+                .saturating_sub(game.consecutive_run_aways()),
+        }
+    }
+
+    /// Returns `true` if these resources are at least as good as `other`
+    /// in every way: any line of play open to `other` is open to them, and
+    /// scores at least as much.
+    fn covers(&self, other: &Resources) -> bool {
+        let weapon = match (self.weapon, other.weapon) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some((value, limit)), Some((other_value, other_limit))) => {
+                value >= other_value && limit >= other_limit
+            }
+        };
+        weapon
+            && self.life_points >= other.life_points
+            && self.potions_left >= other.potions_left
+            && self.run_aways_left >= other.run_aways_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    use crate::{rank::Rank, rules::ScoundrelRules};
+
+    /// Plays the line of a solution on a copy of the game.
+    fn play_line(game: &Scoundrel, line: &[Action]) -> Scoundrel {
+        let mut game = game.clone();
+        for action in line {
+            game.apply(*action).expect("The line is legal");
+        }
+        game
+    }
+
+    /// A short dungeon, quick to solve: 2 suits only.
+    fn small_game(seed: u64) -> Scoundrel {
+        let banned = [Suit::Spades, Suit::Hearts]
+            .into_iter()
+            .flat_map(|suit| Rank::iter().map(move |rank| Card::new(suit, rank)));
+        let rules = ScoundrelRules::builder()
+            .banned_cards(banned)
+            .build()
+            .expect("Valid rules");
+        Scoundrel::from_seed_with_rules(seed, rules)
+    }
+
+    /// A tiny dungeon, quick to search without the solver: monsters from 3
+    /// to 6, weapons and potions of 2 and 3, and few life points. Some deals
+    /// are lost.
+    fn tiny_game(seed: u64) -> Scoundrel {
+        let kept = |card: &Card| match card.suit() {
+            Suit::Clubs => (3..=6).contains(&u8::from(card.rank())),
+            Suit::Diamonds | Suit::Hearts => (2..=3).contains(&u8::from(card.rank())),
+            Suit::Spades => false,
+        };
+        let banned = Suit::iter()
+            .flat_map(|suit| Rank::iter().map(move |rank| Card::new(suit, rank)))
+            .filter(|card| !kept(card));
+        let rules = ScoundrelRules::builder()
+            .starting_life(4)
+            .max_life(5)
+            .banned_cards(banned)
+            .build()
+            .expect("Valid rules");
+        Scoundrel::from_seed_with_rules(seed, rules)
+    }
+
+    /// Best score of every line of play, searched one by one.
+    fn brute_force(game: &Scoundrel) -> i32 {
+        if let Some(score) = game.score() {
+            return score;
+        }
+        game.legal_actions()
+            .into_iter()
+            .map(|action| {
+                let mut next = game.clone();
+                next.apply(action).expect("The action is legal");
+                brute_force(&next)
+            })
+            .max()
+            .expect("A game in progress has legal actions")
+    }
+
+    #[test]
+    fn solver_finds_the_best_score_of_every_line() {
+        for seed in 0..8 {
+            let game = tiny_game(seed).without_undo();
+            let solution = solve(&game);
+
+            assert_eq!(solution.best_score, brute_force(&game));
+            // Won games score at least 1 life point, lost ones at most 0.
+            assert_eq!(solution.winnable, solution.best_score > 0);
+        }
+    }
+
+    #[test]
+    fn best_line_reaches_the_best_score() {
+        for seed in 0..5 {
+            let game = small_game(seed);
+            let solution = solve(&game);
+            let played = play_line(&game, &solution.line);
+
+            assert_ne!(played.state(), GameState::InGame);
+            assert_eq!(played.score(), Some(solution.best_score));
+            assert_eq!(solution.winnable, played.state() == GameState::Win);
+        }
+    }
+
+    #[test]
+    fn solver_beats_any_other_line() {
+        let game = small_game(3);
+        let solution = solve(&game);
+
+        // Always picking the first or the last legal action.
+        for pick_last in [false, true] {
+            let mut other = game.clone();
+            while other.state() == GameState::InGame {
+                let actions = other.legal_actions();
+                let action = if pick_last {
+                    actions[actions.len() - 1]
+                } else {
+                    actions[0]
+                };
+                other.apply(action).expect("The action is legal");
+            }
+            assert!(other.score() <= Some(solution.best_score));
+        }
+    }
+
+    #[test]
+    fn lost_deal_is_not_winnable() {
+        // Only monsters, far more than 1 life point can take.
+        let rules = ScoundrelRules::builder()
+            .starting_life(1)
+            .banned_cards(
+                [Suit::Diamonds, Suit::Hearts, Suit::Spades]
+                    .into_iter()
+                    .flat_map(|suit| Rank::iter().map(move |rank| Card::new(suit, rank))),
+            )
+            .build()
+            .expect("Valid rules");
+        let game = Scoundrel::from_seed_with_rules(1, rules);
+
+        let solution = solve(&game);
+        assert!(!solution.winnable);
+        assert!(solution.best_score < 0);
+    }
+
+    #[test]
+    fn solving_a_finished_game_returns_its_score() {
+        let mut game = small_game(0);
+        let solution = solve(&game);
+        game = play_line(&game, &solution.line);
+
+        let finished = solve(&game);
+        assert!(finished.line.is_empty());
+        assert_eq!(finished.best_score, solution.best_score);
+        assert_eq!(finished.positions, 0);
+    }
+
+    #[test]
+    fn solver_gives_up_past_its_positions() {
+        let game = small_game(2);
+        let solution = solve(&game);
+
+        assert_eq!(solve_within(&game, solution.positions - 1), None);
+        assert_eq!(
+            solve_within(&game, solution.positions),
+            Some(solution.clone())
+        );
+        // A finished game needs no position.
+        let finished = play_line(&game, &solution.line);
+        assert!(solve_within(&finished, 0).is_some());
+    }
+
+    #[test]
+    fn more_resources_cover_fewer() {
+        let resources = Resources {
+            life_points: 10,
+            weapon: Some((5, 9)),
+            potions_left: 1,
+            run_aways_left: 0,
+        };
+        let more_life = Resources {
+            life_points: 12,
+            ..resources
+        };
+        let better_weapon = Resources {
+            weapon: Some((7, 9)),
+            ..resources
+        };
+        let worn_weapon = Resources {
+            weapon: Some((7, 3)),
+            ..resources
+        };
+        let no_weapon = Resources {
+            weapon: None,
+            ..resources
+        };
+
+        assert!(more_life.covers(&resources));
+        assert!(!resources.covers(&more_life));
+        assert!(better_weapon.covers(&resources));
+        assert!(resources.covers(&no_weapon));
+        assert!(!no_weapon.covers(&resources));
+        assert!(!worn_weapon.covers(&resources));
+        assert!(!resources.covers(&worn_weapon));
+    }
+
+    #[test]
+    fn room_order_is_canonical_once_started() {
+        let mut game = small_game(1);
+        game.enter_room().expect("First room");
+        let card = game.room()[0];
+        game.play_card_with(&card, crate::scoundrel::CombatMode::Barehanded)
+            .expect("The card is in the room");
+
+        let mut shuffled = game.clone();
+        shuffled.room.reverse();
+        assert_eq!(Layout::new(&game), Layout::new(&shuffled));
+    }
+}
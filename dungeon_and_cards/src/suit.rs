@@ -1,12 +1,15 @@
 //! A module for playing card suits's functionality.
 use core::fmt;
+use std::str::FromStr;
 use strum_macros::EnumIter;
 
+use crate::card::{CardIndexError, ParseCardError};
+
 /// Enum representing the four standard playing card suits.
 ///
 /// # Examples
 /// ```
-/// use your_crate::Suit;
+/// use dungeon_and_cards::Suit;
 ///
 /// let suit = Suit::Hearts;
 /// assert_eq!(suit.to_string(), "♥️");
@@ -34,9 +37,86 @@ impl fmt::Display for Suit {
     }
 }
 
+impl Suit {
+    /// Returns the letter of the suit in the short card notation, e.g. `C`
+    /// for Clubs.
+    pub fn letter(self) -> char {
+        match self {
+            Suit::Spades => 'S',
+            Suit::Diamonds => 'D',
+            Suit::Clubs => 'C',
+            Suit::Hearts => 'H',
+        }
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    /// Parses a suit, ignoring case, from its emoji (`♣️`), its letter (`C`)
+    /// or its name (`Clubs`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text = s.trim();
+        // The emoji presentation selector is optional.
+        match text.trim_end_matches('\u{fe0f}').to_lowercase().as_str() {
+            "♠" | "s" | "spade" | "spades" => Ok(Suit::Spades),
+            "♦" | "d" | "diamond" | "diamonds" => Ok(Suit::Diamonds),
+            "♣" | "c" | "club" | "clubs" => Ok(Suit::Clubs),
+            "♥" | "h" | "heart" | "hearts" => Ok(Suit::Hearts),
+            _ => Err(ParseCardError::InvalidSuit(text.to_string())),
+        }
+    }
+}
+
+/// Numbers the suits from 0 to 3, in the order of a new deck: Spades,
+/// Diamonds, Clubs then Hearts.
+impl From<Suit> for u8 {
+    fn from(value: Suit) -> Self {
+        match value {
+            Suit::Spades => 0,
+            Suit::Diamonds => 1,
+            Suit::Clubs => 2,
+            Suit::Hearts => 3,
+        }
+    }
+}
+
+impl TryFrom<u8> for Suit {
+    type Error = CardIndexError;
+
+    /// Converts back the number given by `u8::from(suit)`, from 0 to 3.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Suit::Spades),
+            1 => Ok(Suit::Diamonds),
+            2 => Ok(Suit::Clubs),
+            3 => Ok(Suit::Hearts),
+            _ => Err(CardIndexError::InvalidSuit(value)),
+        }
+    }
+}
+
+/// A suit is serialized as its letter, e.g. `"C"`, and deserialized from
+/// any text `FromStr` accepts.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Suit {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.letter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Suit {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use strum::IntoEnumIterator;
 
     #[test]
     fn suit_should_display_an_emoji() {
@@ -45,4 +125,36 @@ mod tests {
         assert_eq!(Suit::Clubs.to_string(), "♣️");
         assert_eq!(Suit::Hearts.to_string(), "♥️");
     }
+
+    #[test]
+    fn suit_numbers_convert_back() {
+        for (number, suit) in (0..).zip(Suit::iter()) {
+            assert_eq!(u8::from(suit), number);
+            assert_eq!(Suit::try_from(number), Ok(suit));
+        }
+        assert_eq!(Suit::try_from(4), Err(CardIndexError::InvalidSuit(4)));
+    }
+
+    #[test]
+    fn suit_letters_parse_back() {
+        for suit in Suit::iter() {
+            assert_eq!(suit.letter().to_string().parse(), Ok(suit));
+        }
+    }
+
+    #[test]
+    fn parse_a_suit() {
+        assert_eq!("♣️".parse(), Ok(Suit::Clubs));
+        assert_eq!("♦".parse(), Ok(Suit::Diamonds));
+        assert_eq!("h".parse(), Ok(Suit::Hearts));
+        assert_eq!(" Spades ".parse(), Ok(Suit::Spades));
+        assert_eq!(
+            "".parse::<Suit>(),
+            Err(ParseCardError::InvalidSuit(String::new()))
+        );
+        assert_eq!(
+            "Cups".parse::<Suit>(),
+            Err(ParseCardError::InvalidSuit("Cups".to_string()))
+        );
+    }
 }